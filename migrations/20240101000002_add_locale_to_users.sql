@@ -0,0 +1,2 @@
+-- Add preferred locale for localized emails
+ALTER TABLE users ADD COLUMN IF NOT EXISTS locale VARCHAR(10) NOT NULL DEFAULT 'en';
@@ -0,0 +1,15 @@
+-- Addresses we must not email (hard bounces, complaints, unsubscribes)
+CREATE TABLE IF NOT EXISTS email_suppressions (
+    email VARCHAR(255) PRIMARY KEY,
+    reason VARCHAR(20) NOT NULL,
+    details TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_email_suppressions_reason ON email_suppressions(reason);
+
+-- Per-user token for one-click unsubscribe links (existing users get a random token)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS unsubscribe_token VARCHAR(64) NOT NULL
+    DEFAULT replace(gen_random_uuid()::text, '-', '');
+
+CREATE UNIQUE INDEX idx_users_unsubscribe_token ON users(unsubscribe_token);
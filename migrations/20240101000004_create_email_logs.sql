@@ -0,0 +1,20 @@
+-- Delivery record of every email job outcome
+CREATE TABLE IF NOT EXISTS email_logs (
+    id VARCHAR(255) PRIMARY KEY,
+    job_id VARCHAR(255) NOT NULL,
+    recipient VARCHAR(255) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    locale VARCHAR(10) NOT NULL,
+    template_version VARCHAR(32),
+    status VARCHAR(20) NOT NULL,
+    message_id VARCHAR(255),
+    provider_response TEXT,
+    error TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    queued_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_email_logs_recipient ON email_logs(LOWER(recipient));
+CREATE INDEX idx_email_logs_kind_status ON email_logs(kind, status);
+CREATE INDEX idx_email_logs_created_at ON email_logs(created_at);
@@ -0,0 +1,2 @@
+-- How notification emails are delivered: instant, hourly or daily digest
+ALTER TABLE users ADD COLUMN IF NOT EXISTS notification_delivery VARCHAR(10) NOT NULL DEFAULT 'instant';
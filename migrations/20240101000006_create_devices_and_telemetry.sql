@@ -0,0 +1,26 @@
+-- Registered IoT devices, owned by a user
+CREATE TABLE IF NOT EXISTS devices (
+    id VARCHAR(255) PRIMARY KEY,
+    owner_id VARCHAR(255) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- SHA-256 of the device key (the key itself is only shown when created or rotated)
+    key_hash VARCHAR(64) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    last_seen_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_devices_owner_id ON devices(owner_id);
+
+-- Telemetry readings ingested from MQTT
+CREATE TABLE IF NOT EXISTS telemetry (
+    device_id VARCHAR(255) NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    metric VARCHAR(100) NOT NULL,
+    value DOUBLE PRECISION NOT NULL,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_telemetry_device_time ON telemetry(device_id, recorded_at);
+CREATE INDEX idx_telemetry_device_metric_time ON telemetry(device_id, metric, recorded_at);
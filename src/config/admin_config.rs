@@ -0,0 +1,28 @@
+/// Admin access configuration
+#[derive(Debug, Clone)]
+pub struct AdminConfig {
+    /// IDs of the users allowed to use admin endpoints
+    ///
+    /// User IDs rather than emails: emails are unverified and can be changed through
+    /// `PUT /api/user`, so anyone could claim an admin address that has no account yet.
+    pub user_ids: Vec<String>,
+}
+
+impl AdminConfig {
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+
+        let user_ids = std::env::var("ADMIN_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect();
+
+        Self { user_ids }
+    }
+
+    pub fn is_admin(&self, user_id: &str) -> bool {
+        self.user_ids.iter().any(|admin| admin == user_id)
+    }
+}
@@ -7,6 +7,8 @@ pub struct AppConfig {
     pub environment: String,
     pub app_name: String,
     pub app_version: String,
+    /// Externally reachable base URL (used in links sent by email)
+    pub public_url: String,
 }
 
 impl AppConfig {
@@ -17,12 +19,18 @@ impl AppConfig {
             .add_source(config::Environment::default())
             .build()?;
 
+        let port = cfg.get_int("PORT").unwrap_or(3000) as u16;
+
         Ok(Self {
             host: cfg.get_string("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            port: cfg.get_int("PORT").unwrap_or(3000) as u16,
+            port,
             environment: cfg.get_string("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
             app_name: cfg.get_string("APP_NAME").unwrap_or_else(|_| "rust-backend-template".to_string()),
             app_version: cfg.get_string("APP_VERSION").unwrap_or_else(|_| "0.1.0".to_string()),
+            public_url: cfg
+                .get_string("PUBLIC_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| format!("http://localhost:{}", port)),
         })
     }
 
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use sqlx::PgPool;
-use crate::config::AppConfig;
-use crate::services::RedisService;
+use crate::config::{AdminConfig, AppConfig, BrokerAuthConfig, EmailConfig};
+use crate::services::{EmailTemplates, Mailer, MqttService, RealtimeHub, RedisService};
 
 /// Application state shared across all handlers and services
 #[derive(Debug, Clone)]
@@ -10,16 +10,48 @@ pub struct AppState {
     pub db: PgPool,
     /// Redis service
     pub redis: RedisService,
+    /// Outgoing email delivery
+    pub mailer: Mailer,
+    /// Email templates (HTML + text, per locale)
+    pub email_templates: Arc<EmailTemplates>,
+    /// Email configuration
+    pub email_config: Arc<EmailConfig>,
+    /// MQTT client (None when MQTT_ENABLED is off)
+    pub mqtt: Option<MqttService>,
+    /// Pushes events to users connected over WebSocket / SSE
+    pub realtime: RealtimeHub,
+    /// Broker authentication and ACL rules
+    pub broker_auth: Arc<BrokerAuthConfig>,
+    /// Users allowed to use admin endpoints
+    pub admin: Arc<AdminConfig>,
     /// Application configuration
     pub config: Arc<AppConfig>,
 }
 
 impl AppState {
     /// Create new AppState
-    pub fn new(db: PgPool, redis: RedisService, config: AppConfig) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: PgPool,
+        redis: RedisService,
+        mailer: Mailer,
+        email_templates: EmailTemplates,
+        email_config: EmailConfig,
+        mqtt: Option<MqttService>,
+        broker_auth: BrokerAuthConfig,
+        admin: AdminConfig,
+        config: AppConfig,
+    ) -> Self {
         Self {
             db,
+            realtime: RealtimeHub::new(redis.clone(), &config.environment),
             redis,
+            mailer,
+            email_templates: Arc::new(email_templates),
+            email_config: Arc::new(email_config),
+            mqtt,
+            broker_auth: Arc::new(broker_auth),
+            admin: Arc::new(admin),
             config: Arc::new(config),
         }
     }
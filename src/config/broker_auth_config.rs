@@ -0,0 +1,111 @@
+use serde::Deserialize;
+
+/// Rules used when no `MQTT_ACL_RULES` are configured
+const DEFAULT_ACL_RULES: &str = "device:all:devices/{id}/#,user:all:users/{id}/#";
+
+/// Who an ACL rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AclRole {
+    Device,
+    User,
+    /// Devices and users
+    Any,
+}
+
+/// What an ACL rule grants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AclAccess {
+    Publish,
+    Subscribe,
+    /// Publish and subscribe
+    All,
+}
+
+impl AclAccess {
+    /// Whether a rule granting `self` allows `requested`
+    pub fn allows(&self, requested: AclAccess) -> bool {
+        *self == AclAccess::All || *self == requested
+    }
+}
+
+/// One ACL rule: `role:access:pattern`
+///
+/// The pattern is an MQTT topic filter that may contain `{id}` (device or user ID),
+/// `{username}` and `{clientid}` placeholders.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AclRule {
+    pub role: AclRole,
+    pub access: AclAccess,
+    pub pattern: String,
+}
+
+impl AclRule {
+    pub fn parse(rule: &str) -> Result<Self, config::ConfigError> {
+        let invalid = |reason: &str| config::ConfigError::Message(format!("Invalid MQTT ACL rule '{}': {}", rule, reason));
+
+        let mut parts = rule.splitn(3, ':').map(str::trim);
+        let (Some(role), Some(access), Some(pattern)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid("expected role:access:pattern"));
+        };
+
+        let role = match role {
+            "device" => AclRole::Device,
+            "user" => AclRole::User,
+            "any" => AclRole::Any,
+            _ => return Err(invalid("role must be device, user or any")),
+        };
+        let access = match access {
+            "publish" => AclAccess::Publish,
+            "subscribe" => AclAccess::Subscribe,
+            "all" => AclAccess::All,
+            _ => return Err(invalid("access must be publish, subscribe or all")),
+        };
+        if pattern.is_empty() {
+            return Err(invalid("pattern is empty"));
+        }
+
+        Ok(Self {
+            role,
+            access,
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+/// Authentication and ACL checks answered for the MQTT broker
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerAuthConfig {
+    /// The backend's own broker credentials (`MQTT_USERNAME` / `MQTT_PASSWORD`), granted superuser access
+    pub service_username: Option<String>,
+    pub service_password: Option<String>,
+    /// Shared secret the broker must present on `/api/mqtt/*` (the endpoints are disabled when unset)
+    pub auth_secret: Option<String>,
+    pub acl_rules: Vec<AclRule>,
+}
+
+impl BrokerAuthConfig {
+    pub fn from_env() -> Result<Self, config::ConfigError> {
+        dotenv::dotenv().ok();
+
+        let cfg = config::Config::builder()
+            .add_source(config::Environment::default())
+            .build()?;
+
+        let non_empty = |key: &str| cfg.get_string(key).ok().filter(|value| !value.trim().is_empty());
+
+        let acl_rules = non_empty("MQTT_ACL_RULES")
+            .unwrap_or_else(|| DEFAULT_ACL_RULES.to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(AclRule::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            service_username: non_empty("MQTT_USERNAME"),
+            service_password: non_empty("MQTT_PASSWORD"),
+            auth_secret: non_empty("MQTT_AUTH_SECRET"),
+            acl_rules,
+        })
+    }
+}
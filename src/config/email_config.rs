@@ -0,0 +1,115 @@
+use serde::Deserialize;
+
+/// SMTP connection security
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    Starttls,
+    /// TLS from the first byte (port 465)
+    Tls,
+    /// No encryption (local SMTP sinks only)
+    None,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfig {
+    /// Transport used for delivery: "smtp", "log" or "file"
+    pub transport: String,
+    pub from: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_pool_size: u32,
+    pub smtp_timeout: u64,
+    /// Maildir root used by the file transport
+    pub file_dir: String,
+    /// Root directory of email templates
+    pub template_dir: String,
+    /// Locale used when a template has no variant for the user's locale
+    pub default_locale: String,
+    /// Storage root for attachments referenced by path
+    pub attachment_dir: String,
+    /// Largest attachment carried inline in a job payload (bytes)
+    pub max_inline_attachment_size: u64,
+    /// Largest single attachment (bytes)
+    pub max_attachment_size: u64,
+    /// Largest combined size of all attachments of one email (bytes)
+    pub max_total_attachment_size: u64,
+    /// Shared secret expected in `X-Webhook-Secret` on bounce/complaint webhooks (disabled when unset)
+    pub webhook_secret: Option<String>,
+    /// UTC hour (0-23) at which daily notification digests are sent
+    pub digest_daily_hour: u32,
+    /// DKIM selector; signing is enabled when a selector and private key are configured
+    pub dkim_selector: Option<String>,
+    /// Signing domain (defaults to the domain of `EMAIL_FROM`)
+    pub dkim_domain: Option<String>,
+    /// "rsa" (PKCS#1 PEM key) or "ed25519" (base64 key)
+    pub dkim_algorithm: String,
+    /// Private key given inline
+    pub dkim_private_key: Option<String>,
+    /// Path of the private key file (used when no inline key is set)
+    pub dkim_private_key_file: Option<String>,
+}
+
+impl EmailConfig {
+    pub fn from_env() -> Result<Self, config::ConfigError> {
+        dotenv::dotenv().ok();
+
+        let cfg = config::Config::builder()
+            .add_source(config::Environment::default())
+            .build()?;
+
+        let smtp_username = cfg.get_string("SMTP_USERNAME").ok();
+        let smtp_password = cfg.get_string("SMTP_PASSWORD").ok();
+        let webhook_secret = cfg.get_string("EMAIL_WEBHOOK_SECRET").ok();
+        let non_empty = |key: &str| cfg.get_string(key).ok().filter(|value| !value.trim().is_empty());
+
+        let smtp_security = match cfg.get_string("SMTP_SECURITY").unwrap_or_else(|_| "starttls".to_string()).to_lowercase().as_str() {
+            "starttls" => SmtpSecurity::Starttls,
+            "tls" => SmtpSecurity::Tls,
+            "none" => SmtpSecurity::None,
+            other => {
+                return Err(config::ConfigError::Message(format!(
+                    "Invalid SMTP_SECURITY '{}' (expected starttls, tls or none)",
+                    other
+                )))
+            }
+        };
+
+        let default_port = match smtp_security {
+            SmtpSecurity::Starttls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        };
+
+        Ok(Self {
+            transport: cfg.get_string("EMAIL_TRANSPORT").unwrap_or_else(|_| "log".to_string()).to_lowercase(),
+            from: cfg.get_string("EMAIL_FROM").unwrap_or_else(|_| "Rust Backend <no-reply@localhost>".to_string()),
+            smtp_host: cfg.get_string("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string()),
+            smtp_port: cfg.get_int("SMTP_PORT").map(|p| p as u16).unwrap_or(default_port),
+            smtp_username: smtp_username.filter(|u| !u.is_empty()),
+            smtp_password: smtp_password.filter(|p| !p.is_empty()),
+            smtp_security,
+            smtp_pool_size: cfg.get_int("SMTP_POOL_SIZE").unwrap_or(5) as u32,
+            smtp_timeout: cfg.get_int("SMTP_TIMEOUT").unwrap_or(10) as u64,
+            file_dir: cfg.get_string("EMAIL_FILE_DIR").unwrap_or_else(|_| "logs/mail".to_string()),
+            template_dir: cfg.get_string("EMAIL_TEMPLATE_DIR").unwrap_or_else(|_| "templates/email".to_string()),
+            default_locale: cfg.get_string("EMAIL_DEFAULT_LOCALE").unwrap_or_else(|_| "en".to_string()),
+            attachment_dir: cfg.get_string("EMAIL_ATTACHMENT_DIR").unwrap_or_else(|_| "storage/attachments".to_string()),
+            max_inline_attachment_size: cfg.get_int("EMAIL_MAX_INLINE_ATTACHMENT_SIZE").unwrap_or(256 * 1024) as u64,
+            max_attachment_size: cfg.get_int("EMAIL_MAX_ATTACHMENT_SIZE").unwrap_or(10 * 1024 * 1024) as u64,
+            max_total_attachment_size: cfg.get_int("EMAIL_MAX_TOTAL_ATTACHMENT_SIZE").unwrap_or(20 * 1024 * 1024) as u64,
+            webhook_secret: webhook_secret.filter(|s| !s.is_empty()),
+            digest_daily_hour: cfg.get_int("EMAIL_DIGEST_DAILY_HOUR").unwrap_or(8).clamp(0, 23) as u32,
+            dkim_selector: non_empty("DKIM_SELECTOR"),
+            dkim_domain: non_empty("DKIM_DOMAIN"),
+            dkim_algorithm: cfg.get_string("DKIM_ALGORITHM").unwrap_or_else(|_| "rsa".to_string()).to_lowercase(),
+            // Env files can't hold multi-line values, so allow "\n" escapes in inline PEM keys
+            dkim_private_key: non_empty("DKIM_PRIVATE_KEY").map(|key| key.replace("\\n", "\n")),
+            dkim_private_key_file: non_empty("DKIM_PRIVATE_KEY_FILE"),
+        })
+    }
+}
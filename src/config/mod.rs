@@ -3,9 +3,15 @@ pub mod database;
 pub mod redis_config;
 pub mod mqtt_config;
 pub mod app_state;
+pub mod email_config;
+pub mod broker_auth_config;
+pub mod admin_config;
 
 pub use app_config::AppConfig;
 pub use database::DatabaseConfig;
 pub use redis_config::RedisConfig;
 pub use mqtt_config::MqttConfig;
 pub use app_state::AppState;
+pub use email_config::EmailConfig;
+pub use admin_config::AdminConfig;
+pub use broker_auth_config::{AclAccess, AclRole, BrokerAuthConfig};
@@ -2,11 +2,42 @@ use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct MqttConfig {
+    /// Connect to the broker at startup (MQTT is optional)
+    pub enabled: bool,
     pub broker: String,
+    /// `{MQTT_CLIENT_ID}-{instance}`, unique per replica so they don't take over each other's connection
     pub client_id: String,
     pub username: Option<String>,
     pub password: Option<String>,
     pub keep_alive: u64,
+    /// Seconds the broker keeps our session (subscriptions, unacknowledged messages) after a
+    /// disconnect; 0 starts a clean session on every connect
+    pub session_expiry: u32,
+    /// Seconds to wait for the first connection at startup before continuing degraded
+    pub connect_timeout: u64,
+    /// Longest wait between reconnect attempts in seconds (the delay doubles up to this)
+    pub reconnect_max_delay: u64,
+    /// QoS 1/2 publishes held in memory while the broker is unreachable (0 disables buffering)
+    pub offline_buffer_size: usize,
+    /// QoS (0, 1 or 2) for `subscribe` / `publish` calls that don't pass one
+    pub default_qos: u8,
+    /// Retained presence topic: the broker publishes `offline_payload` here if we drop (last will),
+    /// and we publish `online_payload` after every connect
+    pub status_topic: Option<String>,
+    pub online_payload: String,
+    pub offline_payload: String,
+    /// Topic receiving replies to `request` calls (defaults to a unique per-instance topic)
+    pub response_topic: Option<String>,
+    /// Shared subscription group of this deployment; shared routes deliver each message to one member
+    pub shared_group: String,
+    /// PEM CA bundle for `mqtts://` / `wss://` (system roots when unset)
+    pub ca_file: Option<String>,
+    /// PEM client certificate for mutual TLS (requires `client_key_file`)
+    pub client_cert_file: Option<String>,
+    /// PEM client private key for mutual TLS (requires `client_cert_file`)
+    pub client_key_file: Option<String>,
+    /// ALPN protocols offered during the TLS handshake
+    pub alpn: Vec<String>,
 }
 
 impl MqttConfig {
@@ -20,12 +51,45 @@ impl MqttConfig {
         let username = cfg.get_string("MQTT_USERNAME").ok();
         let password = cfg.get_string("MQTT_PASSWORD").ok();
 
+        let non_empty = |key: &str| cfg.get_string(key).ok().filter(|value| !value.trim().is_empty());
+
+        // A stable instance name (e.g. the pod's hostname) lets a restarted replica resume its session
+        let instance = non_empty("MQTT_INSTANCE_ID")
+            .or_else(|| non_empty("HOSTNAME"))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..12].to_string());
+        let client_id = format!(
+            "{}-{}",
+            non_empty("MQTT_CLIENT_ID").unwrap_or_else(|| "rust-backend-template".to_string()),
+            instance
+        );
+
         Ok(Self {
+            enabled: cfg.get_bool("MQTT_ENABLED").unwrap_or(false),
             broker: cfg.get_string("MQTT_BROKER").unwrap_or_else(|_| "mqtt://localhost:1883".to_string()),
-            client_id: cfg.get_string("MQTT_CLIENT_ID").unwrap_or_else(|_| "rust-backend-template".to_string()),
+            client_id,
             username: if username.as_ref().map_or(false, |u| !u.is_empty()) { username } else { None },
             password: if password.as_ref().map_or(false, |p| !p.is_empty()) { password } else { None },
             keep_alive: cfg.get_int("MQTT_KEEP_ALIVE").unwrap_or(60) as u64,
+            session_expiry: cfg.get_int("MQTT_SESSION_EXPIRY").unwrap_or(300).max(0) as u32,
+            connect_timeout: cfg.get_int("MQTT_CONNECT_TIMEOUT").unwrap_or(5) as u64,
+            reconnect_max_delay: cfg.get_int("MQTT_RECONNECT_MAX_DELAY").unwrap_or(60).max(1) as u64,
+            offline_buffer_size: cfg.get_int("MQTT_OFFLINE_BUFFER_SIZE").unwrap_or(0).max(0) as usize,
+            default_qos: cfg.get_int("MQTT_QOS").unwrap_or(1) as u8,
+            status_topic: non_empty("MQTT_STATUS_TOPIC"),
+            online_payload: cfg.get_string("MQTT_ONLINE_PAYLOAD").unwrap_or_else(|_| "online".to_string()),
+            offline_payload: cfg.get_string("MQTT_OFFLINE_PAYLOAD").unwrap_or_else(|_| "offline".to_string()),
+            response_topic: non_empty("MQTT_RESPONSE_TOPIC"),
+            shared_group: non_empty("MQTT_SHARED_GROUP").unwrap_or_else(|| "rust-backend-template".to_string()),
+            ca_file: non_empty("MQTT_CA_FILE"),
+            client_cert_file: non_empty("MQTT_CLIENT_CERT_FILE"),
+            client_key_file: non_empty("MQTT_CLIENT_KEY_FILE"),
+            alpn: cfg
+                .get_string("MQTT_ALPN")
+                .unwrap_or_default()
+                .split(',')
+                .map(|protocol| protocol.trim().to_string())
+                .filter(|protocol| !protocol.is_empty())
+                .collect(),
         })
     }
 }
@@ -1,5 +1,9 @@
-use deadpool_redis::{Config, Pool, Runtime};
+use deadpool_redis::{Config, Pool, PoolConfig, Runtime, Timeouts};
 use redis::RedisError;
+use std::time::Duration;
+
+/// Longest wait for a pooled connection, so a hung Redis fails calls instead of stalling them
+const POOL_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct RedisConfig {
@@ -35,7 +39,15 @@ impl RedisConfig {
     pub fn create_pool(&self) -> Result<Pool, RedisError> {
         let redis_url = self.build_redis_url();
 
-        let cfg = Config::from_url(&redis_url);
+        let mut cfg = Config::from_url(&redis_url);
+        cfg.pool = Some(PoolConfig {
+            timeouts: Timeouts {
+                wait: Some(POOL_TIMEOUT),
+                create: Some(POOL_TIMEOUT),
+                recycle: Some(POOL_TIMEOUT),
+            },
+            ..PoolConfig::default()
+        });
 
         cfg.create_pool(Some(Runtime::Tokio1))
             .map_err(|e| RedisError::from((redis::ErrorKind::IoError, "Failed to create pool", e.to_string())))
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Broker authentication request (mosquitto-go-auth `getuser_uri` / EMQX HTTP authentication)
+///
+/// The password is either the account password, the device key, or a JWT issued by `/api/auth/login`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerAuthRequest {
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub clientid: String,
+}
+
+/// Broker superuser check (mosquitto-go-auth `superuser_uri`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerSuperuserRequest {
+    pub username: String,
+}
+
+/// Broker ACL check (mosquitto-go-auth `aclcheck_uri` / EMQX HTTP authorization)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerAclRequest {
+    pub username: String,
+    #[serde(default)]
+    pub clientid: String,
+    pub topic: String,
+    /// mosquitto-go-auth access: 1 read, 2 write, 3 read and write, 4 subscribe
+    pub acc: Option<u8>,
+    /// EMQX action: `publish` or `subscribe`
+    pub action: Option<String>,
+}
+
+/// Shared secret passed in the query string, for brokers that can't send custom headers
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerSecretQuery {
+    pub secret: Option<String>,
+}
+
+/// Decision returned to the broker
+///
+/// Always sent with status 200: mosquitto-go-auth (`http_response_mode json`) reads `ok`,
+/// EMQX reads `result` and `is_superuser`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerAuthResponse {
+    pub result: String,
+    pub ok: bool,
+    pub is_superuser: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BrokerAuthResponse {
+    pub fn allow(is_superuser: bool) -> Self {
+        Self {
+            result: "allow".to_string(),
+            ok: true,
+            is_superuser,
+            error: None,
+        }
+    }
+
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self {
+            result: "deny".to_string(),
+            ok: false,
+            is_superuser: false,
+            error: Some(reason.into()),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+/// Device response (without the key hash)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceResponse {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub is_active: bool,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create device request
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateDeviceRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+}
+
+/// Update device request
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateDeviceRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    pub is_active: Option<bool>,
+}
+
+/// Device with its key, returned only when the device is created or its key rotated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCredentialsResponse {
+    pub device: DeviceResponse,
+    /// Device key sent with every telemetry message (store it now, it cannot be retrieved later)
+    pub key: String,
+}
+
+/// Telemetry message published by a device on `devices/{id}/telemetry`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelemetryMessage {
+    /// Device key issued at registration
+    pub key: String,
+    pub readings: Vec<TelemetryReadingInput>,
+}
+
+/// One reading in a telemetry message
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelemetryReadingInput {
+    pub metric: String,
+    pub value: f64,
+    /// Measurement time (defaults to the time the message is received)
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Telemetry query filters
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelemetryQuery {
+    /// Only this metric (all metrics when omitted)
+    pub metric: Option<String>,
+    /// Range start (defaults to 24 hours before `to`)
+    pub from: Option<DateTime<Utc>>,
+    /// Range end, exclusive (defaults to now)
+    pub to: Option<DateTime<Utc>>,
+    /// Downsampling bucket in seconds (raw readings when omitted)
+    pub interval: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Aggregated readings of one metric in one time bucket (a single reading when not downsampled)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TelemetryPoint {
+    pub metric: String,
+    /// Bucket start (or reading time)
+    pub time: DateTime<Utc>,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub count: i64,
+}
+
+/// Telemetry query result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryResponse {
+    pub device_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub interval: Option<i64>,
+    pub points: Vec<TelemetryPoint>,
+}
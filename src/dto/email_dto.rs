@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::EmailLog;
+
+/// Kind of delivery event reported by the email provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailWebhookEventType {
+    Bounce,
+    Complaint,
+}
+
+/// Bounce classification (only hard bounces suppress the address)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BounceType {
+    Hard,
+    Soft,
+}
+
+/// Provider-agnostic bounce/complaint notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailWebhookEvent {
+    #[serde(rename = "type")]
+    pub event_type: EmailWebhookEventType,
+    pub email: String,
+    /// Defaults to hard when omitted
+    pub bounce_type: Option<BounceType>,
+    /// Provider diagnostic (e.g. SMTP status line)
+    pub description: Option<String>,
+}
+
+/// Webhook body: a single event or a batch
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EmailWebhookPayload {
+    Batch { events: Vec<EmailWebhookEvent> },
+    Single(EmailWebhookEvent),
+}
+
+impl EmailWebhookPayload {
+    pub fn into_events(self) -> Vec<EmailWebhookEvent> {
+        match self {
+            EmailWebhookPayload::Batch { events } => events,
+            EmailWebhookPayload::Single(event) => vec![event],
+        }
+    }
+}
+
+/// Webhook processing summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailWebhookResponse {
+    /// Addresses added to the suppression list
+    pub suppressed: usize,
+    /// Events that did not suppress anything (e.g. soft bounces, addresses already suppressed)
+    pub ignored: usize,
+}
+
+/// Email log search filters (all optional)
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailLogQuery {
+    /// Exact recipient address (case-insensitive)
+    pub recipient: Option<String>,
+    pub kind: Option<String>,
+    pub status: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Page of email log entries, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailLogListResponse {
+    pub items: Vec<EmailLog>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Email preview options
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailPreviewQuery {
+    pub locale: Option<String>,
+    /// "html" (default), "text" or "json"
+    pub format: Option<String>,
+}
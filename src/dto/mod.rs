@@ -1,4 +1,8 @@
 pub mod user_dto;
+pub mod email_dto;
+pub mod device_dto;
+pub mod broker_dto;
+pub mod realtime_dto;
 
 pub use user_dto::{
     CreateUserRequest,
@@ -7,4 +11,33 @@ pub use user_dto::{
     LoginRequest,
     LoginResponse,
     RegisterResponse,
+    NotificationPreferences,
 };
+pub use email_dto::{
+    BounceType,
+    EmailWebhookEvent,
+    EmailWebhookEventType,
+    EmailWebhookPayload,
+    EmailWebhookResponse,
+    EmailLogQuery,
+    EmailLogListResponse,
+    EmailPreviewQuery,
+};
+pub use device_dto::{
+    CreateDeviceRequest,
+    UpdateDeviceRequest,
+    DeviceResponse,
+    DeviceCredentialsResponse,
+    TelemetryMessage,
+    TelemetryQuery,
+    TelemetryPoint,
+    TelemetryResponse,
+};
+pub use broker_dto::{
+    BrokerAuthRequest,
+    BrokerSuperuserRequest,
+    BrokerAclRequest,
+    BrokerSecretQuery,
+    BrokerAuthResponse,
+};
+pub use realtime_dto::EventStreamQuery;
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// Query for the WebSocket / SSE endpoints
+///
+/// Browsers can't set headers on `EventSource` or `WebSocket`, so the JWT may be passed here
+/// instead of in the Authorization header.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventStreamQuery {
+    pub token: Option<String>,
+}
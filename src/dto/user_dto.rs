@@ -2,12 +2,15 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::models::NotificationDelivery;
+
 /// User response (without sensitive data)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: String,
     pub email: String,
     pub name: Option<String>,
+    pub locale: String,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -24,6 +27,9 @@ pub struct CreateUserRequest {
 
     #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
     pub name: Option<String>,
+
+    #[validate(length(min = 2, max = 10, message = "Locale must be between 2 and 10 characters"))]
+    pub locale: Option<String>,
 }
 
 /// Update user request
@@ -35,6 +41,9 @@ pub struct UpdateUserRequest {
     #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
     pub name: Option<String>,
 
+    #[validate(length(min = 2, max = 10, message = "Locale must be between 2 and 10 characters"))]
+    pub locale: Option<String>,
+
     pub is_active: Option<bool>,
 }
 
@@ -60,3 +69,10 @@ pub struct LoginResponse {
 pub struct RegisterResponse {
     pub user: UserResponse,
 }
+
+/// Notification email preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    /// "instant", "hourly" or "daily"
+    pub notification_delivery: NotificationDelivery,
+}
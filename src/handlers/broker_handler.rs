@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+
+use crate::config::AppState;
+use crate::dto::{BrokerAclRequest, BrokerAuthRequest, BrokerAuthResponse, BrokerSecretQuery, BrokerSuperuserRequest};
+use crate::interceptors::AppError;
+use crate::services::BrokerAuthService;
+use crate::utils::constant_time_eq;
+
+/// Header carrying the shared broker secret
+const BROKER_SECRET_HEADER: &str = "x-broker-secret";
+
+/// Authenticate a client connecting to the MQTT broker
+pub async fn broker_auth(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<BrokerSecretQuery>,
+    Json(request): Json<BrokerAuthRequest>,
+) -> Result<Json<BrokerAuthResponse>, AppError> {
+    verify_broker_secret(&state, &headers, &query)?;
+
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let broker_auth_service = BrokerAuthService::new(state.clone());
+    let response = match broker_auth_service.authenticate(&request, bearer).await? {
+        Some(identity) => BrokerAuthResponse::allow(identity.is_superuser()),
+        None => {
+            tracing::warn!("MQTT login rejected for '{}' (client '{}')", request.username, request.clientid);
+            BrokerAuthResponse::deny("Invalid credentials")
+        }
+    };
+
+    Ok(Json(response))
+}
+
+/// Tell the MQTT broker whether a user bypasses ACL checks
+pub async fn broker_superuser(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<BrokerSecretQuery>,
+    Json(request): Json<BrokerSuperuserRequest>,
+) -> Result<Json<BrokerAuthResponse>, AppError> {
+    verify_broker_secret(&state, &headers, &query)?;
+
+    let broker_auth_service = BrokerAuthService::new(state.clone());
+
+    Ok(Json(if broker_auth_service.is_superuser(&request.username) {
+        BrokerAuthResponse::allow(true)
+    } else {
+        BrokerAuthResponse::deny("Not a superuser")
+    }))
+}
+
+/// Check whether a broker client may publish to or subscribe on a topic
+pub async fn broker_acl(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<BrokerSecretQuery>,
+    Json(request): Json<BrokerAclRequest>,
+) -> Result<Json<BrokerAuthResponse>, AppError> {
+    verify_broker_secret(&state, &headers, &query)?;
+
+    let broker_auth_service = BrokerAuthService::new(state.clone());
+
+    let response = if broker_auth_service.check_acl(&request).await? {
+        BrokerAuthResponse::allow(false)
+    } else {
+        tracing::debug!("MQTT ACL denied '{}' on '{}'", request.username, request.topic);
+        BrokerAuthResponse::deny("Not authorized for this topic")
+    };
+
+    Ok(Json(response))
+}
+
+/// Only the broker may call these endpoints: it proves itself with `MQTT_AUTH_SECRET`
+///
+/// The `X-Broker-Secret` header is preferred; `?secret=` is accepted for brokers that can't send
+/// custom headers (mosquitto-go-auth).
+fn verify_broker_secret(state: &AppState, headers: &HeaderMap, query: &BrokerSecretQuery) -> Result<(), AppError> {
+    let expected = state
+        .broker_auth
+        .auth_secret
+        .as_deref()
+        .ok_or_else(|| AppError::Forbidden("Broker authentication is disabled".to_string()))?;
+
+    let provided = headers
+        .get(BROKER_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or(query.secret.as_deref())
+        .ok_or_else(|| AppError::Unauthorized("Missing broker secret".to_string()))?;
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(AppError::Unauthorized("Invalid broker secret".to_string()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    Json,
+};
+
+use crate::config::AppState;
+use crate::dto::{
+    CreateDeviceRequest, DeviceCredentialsResponse, DeviceResponse, TelemetryQuery, TelemetryResponse,
+    UpdateDeviceRequest,
+};
+use crate::interceptors::{ApiSuccess, AppError};
+use crate::middleware::Claims;
+use crate::services::DeviceService;
+
+/// List the current user's devices
+pub async fn list_devices(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<ApiSuccess<Vec<DeviceResponse>>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let devices = device_service.list(&claims.id).await?;
+
+    Ok(ApiSuccess::new("Devices retrieved successfully", devices))
+}
+
+/// Register a device (the response contains its key, shown only once)
+pub async fn create_device(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateDeviceRequest>,
+) -> Result<ApiSuccess<DeviceCredentialsResponse>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let device = device_service.create(&claims.id, request).await?;
+
+    Ok(ApiSuccess::new("Device created successfully", device))
+}
+
+/// Get one of the current user's devices
+pub async fn get_device(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<DeviceResponse>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let device = device_service.get(&claims.id, &id).await?;
+
+    Ok(ApiSuccess::new("Device retrieved successfully", device))
+}
+
+/// Update a device
+pub async fn update_device(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateDeviceRequest>,
+) -> Result<ApiSuccess<DeviceResponse>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let device = device_service.update(&claims.id, &id, request).await?;
+
+    Ok(ApiSuccess::new("Device updated successfully", device))
+}
+
+/// Delete a device and its telemetry
+pub async fn delete_device(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<()>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    device_service.delete(&claims.id, &id).await?;
+
+    Ok(ApiSuccess::<()>::new_without_data("Device deleted successfully"))
+}
+
+/// Issue a new device key (the old one stops working)
+pub async fn rotate_device_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<DeviceCredentialsResponse>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let device = device_service.rotate_key(&claims.id, &id).await?;
+
+    Ok(ApiSuccess::new("Device key rotated successfully", device))
+}
+
+/// Query a device's telemetry by time range, optionally downsampled
+pub async fn get_telemetry(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(query): Query<TelemetryQuery>,
+) -> Result<ApiSuccess<TelemetryResponse>, AppError> {
+    let device_service = DeviceService::new(state.clone());
+    let telemetry = device_service.telemetry(&claims.id, &id, &query).await?;
+
+    Ok(ApiSuccess::new("Telemetry retrieved successfully", telemetry))
+}
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+
+use crate::config::AppState;
+use crate::dto::{EmailLogListResponse, EmailLogQuery, EmailPreviewQuery, EmailWebhookPayload, EmailWebhookResponse};
+use crate::interceptors::{ApiSuccess, AppError};
+use crate::models::EmailLog;
+use crate::services::{EmailTemplate, EmailLogService, SuppressionService};
+use crate::utils::{constant_time_eq, escape_html};
+
+/// Header carrying the shared webhook secret
+const WEBHOOK_SECRET_HEADER: &str = "x-webhook-secret";
+
+/// Receive bounce/complaint notifications from the email provider
+pub async fn email_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<EmailWebhookPayload>,
+) -> Result<ApiSuccess<EmailWebhookResponse>, AppError> {
+    let expected = state
+        .email_config
+        .webhook_secret
+        .as_deref()
+        .ok_or_else(|| AppError::Forbidden("Email webhook is disabled".to_string()))?;
+
+    let provided = headers
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::Unauthorized("Missing webhook secret".to_string()))?;
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(AppError::Unauthorized("Invalid webhook secret".to_string()));
+    }
+
+    let suppression_service = SuppressionService::new(state.clone());
+    let response = suppression_service.handle_webhook_events(payload.into_events()).await?;
+
+    Ok(ApiSuccess::new("Webhook processed successfully", response))
+}
+
+/// Unsubscribe landing page linked from email footers (confirms with a POST)
+pub async fn unsubscribe_page(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Html<String> {
+    unsubscribe_html(
+        &state.config.app_name,
+        &format!(
+            r#"<p>Stop receiving notification emails?</p>
+<form method="post" action="/api/email/unsubscribe/{}"><button type="submit">Unsubscribe</button></form>"#,
+            escape_html(&token)
+        ),
+    )
+}
+
+/// One-click unsubscribe (RFC 8058 `List-Unsubscribe-Post`) and landing page form target
+pub async fn unsubscribe(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Html<String>, AppError> {
+    let suppression_service = SuppressionService::new(state.clone());
+    suppression_service.unsubscribe(&token).await?;
+
+    Ok(unsubscribe_html(
+        &state.config.app_name,
+        "<p>You have been unsubscribed from notification emails. Account emails such as password resets will still be delivered.</p>",
+    ))
+}
+
+/// Search the email delivery log
+pub async fn search_email_logs(
+    State(state): State<AppState>,
+    Query(query): Query<EmailLogQuery>,
+) -> Result<ApiSuccess<EmailLogListResponse>, AppError> {
+    let log_service = EmailLogService::new(state.clone());
+    let logs = log_service.search(&query).await?;
+
+    Ok(ApiSuccess::new("Email logs retrieved successfully", logs))
+}
+
+/// Get one email log entry
+pub async fn get_email_log(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<EmailLog>, AppError> {
+    let log_service = EmailLogService::new(state.clone());
+    let log = log_service.find(&id).await?;
+
+    Ok(ApiSuccess::new("Email log retrieved successfully", log))
+}
+
+/// Render an email kind with sample data (nothing is sent)
+pub async fn preview_email(
+    State(state): State<AppState>,
+    Path(kind): Path<String>,
+    Query(query): Query<EmailPreviewQuery>,
+) -> Result<Response, AppError> {
+    let email_kind = EmailTemplate::from_name(&kind).map(EmailTemplate::sample).ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Unknown email kind '{}' (expected one of: {})",
+            kind,
+            EmailTemplate::names().join(", ")
+        ))
+    })?;
+
+    let locale = query
+        .locale
+        .unwrap_or_else(|| state.email_templates.default_locale().to_string());
+    let unsubscribe_url = email_kind
+        .is_unsubscribable()
+        .then(|| format!("{}/api/email/unsubscribe/preview", state.config.public_url));
+
+    let rendered = email_kind.render(&state.email_templates, &locale, unsubscribe_url.as_deref())?;
+
+    match query.format.as_deref().unwrap_or("html") {
+        "html" => Ok(Html(rendered.html).into_response()),
+        "text" => Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], rendered.text).into_response()),
+        "json" => Ok(ApiSuccess::new("Email preview rendered successfully", rendered).into_response()),
+        other => Err(AppError::BadRequest(format!(
+            "Unknown preview format '{}' (expected html, text or json)",
+            other
+        ))),
+    }
+}
+
+fn unsubscribe_html(app_name: &str, body: &str) -> Html<String> {
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Unsubscribe</title>
+</head>
+<body style="font-family: sans-serif; margin: 2rem;">
+<h1>{}</h1>
+{}
+</body>
+</html>"#,
+        escape_html(app_name),
+        body
+    ))
+}
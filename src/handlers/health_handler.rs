@@ -1,14 +1,36 @@
-use axum::Json;
+use axum::extract::State;
 use serde_json::{json, Value};
 
+use crate::config::AppState;
 use crate::interceptors::{ApiSuccess, AppError};
 
 /// Health check endpoint
-pub async fn health_check() -> Result<ApiSuccess<Value>, AppError> {
+///
+/// Optional components that are down (e.g. the MQTT broker) report "degraded" rather than failing.
+pub async fn health_check(State(state): State<AppState>) -> Result<ApiSuccess<Value>, AppError> {
+    let mqtt = match &state.mqtt {
+        None => "disabled",
+        Some(mqtt) if mqtt.is_connected() => "connected",
+        Some(_) => "disconnected",
+    };
+
+    let mqtt_buffered = match &state.mqtt {
+        Some(mqtt) => Some(mqtt.buffered_count().await),
+        None => None,
+    };
+
+    let status = if mqtt == "disconnected" { "degraded" } else { "ok" };
+
     let data = json!({
-        "status": "ok",
+        "status": status,
         "timestamp": chrono::Utc::now().to_rfc3339(),
+        "components": {
+            "mqtt": mqtt,
+            "mqtt_buffered": mqtt_buffered,
+        },
     });
 
-    Ok(ApiSuccess::new("Service is healthy", data))
+    let message = if status == "ok" { "Service is healthy" } else { "Service is degraded" };
+
+    Ok(ApiSuccess::new(message, data))
 }
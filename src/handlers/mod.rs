@@ -1,7 +1,17 @@
 pub mod auth_handler;
 pub mod user_handler;
 pub mod health_handler;
+pub mod queue_handler;
+pub mod email_handler;
+pub mod device_handler;
+pub mod broker_handler;
+pub mod realtime_handler;
 
 pub use auth_handler::{login, register};
-pub use user_handler::{get_user, update_user, delete_user};
+pub use user_handler::{get_user, update_user, delete_user, get_preferences, update_preferences};
 pub use health_handler::health_check;
+pub use queue_handler::{list_queues, queue_dashboard, get_queue_stats, pause_queue, resume_queue, drain_queue};
+pub use email_handler::{email_webhook, unsubscribe_page, unsubscribe, search_email_logs, get_email_log, preview_email};
+pub use device_handler::{list_devices, create_device, get_device, update_device, delete_device, rotate_device_key, get_telemetry};
+pub use broker_handler::{broker_auth, broker_superuser, broker_acl};
+pub use realtime_handler::{event_stream, event_socket};
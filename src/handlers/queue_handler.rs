@@ -0,0 +1,103 @@
+use axum::{extract::Path, response::Html};
+use serde_json::{json, Value};
+
+use crate::interceptors::{ApiSuccess, AppError};
+use crate::queue::{QueueManager, QueueStats};
+use crate::utils::escape_html;
+
+/// List all queues with their stats
+pub async fn list_queues() -> Result<ApiSuccess<Vec<QueueStats>>, AppError> {
+    let stats = QueueManager::global().get_all_stats().await?;
+
+    Ok(ApiSuccess::new("Queues retrieved successfully", stats))
+}
+
+/// Server-rendered overview of all queues (refreshes every 10 seconds)
+pub async fn queue_dashboard() -> Result<Html<String>, AppError> {
+    let stats = QueueManager::global().get_all_stats().await?;
+
+    let rows: String = stats
+        .iter()
+        .map(|queue| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.1}</td><td>{}</td></tr>",
+                escape_html(&queue.name),
+                if queue.paused { "paused" } else { "running" },
+                queue.waiting,
+                queue.processing,
+                queue.delayed,
+                queue.succeeded,
+                queue.failed,
+                queue.throughput_per_minute,
+                queue.oldest_waiting_secs.map_or_else(|| "-".to_string(), |secs| format!("{}s", secs)),
+            )
+        })
+        .collect();
+
+    Ok(Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta http-equiv="refresh" content="10">
+<title>Queues</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ padding: 0.4rem 0.8rem; border-bottom: 1px solid #ddd; text-align: right; }}
+th:first-child, td:first-child {{ text-align: left; }}
+</style>
+</head>
+<body>
+<h1>Queues</h1>
+<table>
+<tr><th>Queue</th><th>State</th><th>Waiting</th><th>Processing</th><th>Delayed</th><th>Succeeded</th><th>Failed</th><th>Jobs/min</th><th>Oldest waiting</th></tr>
+{}
+</table>
+<p>Updated {}</p>
+</body>
+</html>"#,
+        rows,
+        chrono::Utc::now().to_rfc3339(),
+    )))
+}
+
+/// Get stats for a queue
+pub async fn get_queue_stats(
+    Path(name): Path<String>,
+) -> Result<ApiSuccess<QueueStats>, AppError> {
+    let queue = QueueManager::global().find_queue(&name).await?;
+    let stats = queue.get_stats().await?;
+
+    Ok(ApiSuccess::new("Queue stats retrieved successfully", stats))
+}
+
+/// Pause a queue on all instances
+pub async fn pause_queue(
+    Path(name): Path<String>,
+) -> Result<ApiSuccess<()>, AppError> {
+    let queue = QueueManager::global().find_queue(&name).await?;
+    queue.pause().await?;
+
+    Ok(ApiSuccess::<()>::new_without_data(format!("Queue '{}' paused", name)))
+}
+
+/// Resume a paused queue
+pub async fn resume_queue(
+    Path(name): Path<String>,
+) -> Result<ApiSuccess<()>, AppError> {
+    let queue = QueueManager::global().find_queue(&name).await?;
+    queue.resume().await?;
+
+    Ok(ApiSuccess::<()>::new_without_data(format!("Queue '{}' resumed", name)))
+}
+
+/// Remove all waiting jobs from a queue
+pub async fn drain_queue(
+    Path(name): Path<String>,
+) -> Result<ApiSuccess<Value>, AppError> {
+    let queue = QueueManager::global().find_queue(&name).await?;
+    let removed = queue.drain().await?;
+
+    Ok(ApiSuccess::new(format!("Queue '{}' drained", name), json!({ "removed": removed })))
+}
@@ -0,0 +1,106 @@
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use chrono::Utc;
+use futures_util::stream::{self, Stream};
+use std::convert::Infallible;
+use tokio::time::{sleep_until, Duration, Instant};
+
+use crate::config::AppState;
+use crate::dto::EventStreamQuery;
+use crate::interceptors::AppError;
+use crate::middleware::{verify_token, Claims};
+use crate::services::{UserEvent, UserSubscription};
+
+/// Stream the user's events as server-sent events
+pub async fn event_stream(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let claims = authenticate(&headers, &query)?;
+    let expires_at = token_deadline(&claims);
+    let subscription = state.realtime.subscribe(&claims.id);
+
+    // Ends when the token expires; the client reconnects with a fresh one
+    let events = stream::unfold(subscription, move |mut subscription| async move {
+        tokio::select! {
+            event = subscription.recv() => event.map(|event| (Ok(sse_event(&event)), subscription)),
+            _ = sleep_until(expires_at) => None,
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Push the user's events over a WebSocket (one JSON text message per event)
+pub async fn event_socket(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let claims = authenticate(&headers, &query)?;
+    let expires_at = token_deadline(&claims);
+    let subscription = state.realtime.subscribe(&claims.id);
+
+    Ok(upgrade.on_upgrade(move |socket| push_events(socket, subscription, expires_at)))
+}
+
+async fn push_events(mut socket: WebSocket, mut subscription: UserSubscription, expires_at: Instant) {
+    loop {
+        tokio::select! {
+            event = subscription.recv() => {
+                let Some(event) = event else { break };
+                let Ok(text) = serde_json::to_string(&event) else { continue };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            // Incoming messages are ignored (pings are answered automatically)
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = sleep_until(expires_at) => {
+                let close = CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "Token expired".into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                break;
+            }
+        }
+    }
+}
+
+/// Verify the JWT from the Authorization header or the `token` query parameter
+fn authenticate(headers: &HeaderMap, query: &EventStreamQuery) -> Result<Claims, AppError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(query.token.as_deref())
+        .ok_or_else(|| AppError::Unauthorized("Missing token".to_string()))?;
+
+    verify_token(token)
+}
+
+fn token_deadline(claims: &Claims) -> Instant {
+    let remaining = (claims.exp - Utc::now().timestamp()).max(0) as u64;
+    Instant::now() + Duration::from_secs(remaining)
+}
+
+fn sse_event(event: &UserEvent) -> Event {
+    Event::default()
+        .event(&event.event_type)
+        .data(serde_json::to_string(event).unwrap_or_default())
+}
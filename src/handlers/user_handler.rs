@@ -5,7 +5,7 @@ use axum::{
 };
 
 use crate::config::AppState;
-use crate::dto::{UpdateUserRequest, UserResponse};
+use crate::dto::{NotificationPreferences, UpdateUserRequest, UserResponse};
 use crate::interceptors::{ApiSuccess, AppError};
 use crate::middleware::Claims;
 use crate::services::UserService;
@@ -43,3 +43,26 @@ pub async fn delete_user(
 
     Ok(ApiSuccess::<()>::new_without_data("User deleted successfully"))
 }
+
+/// Get notification preferences
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<ApiSuccess<NotificationPreferences>, AppError> {
+    let user_service = UserService::new(state.clone());
+    let preferences = user_service.get_preferences(&claims.id).await?;
+
+    Ok(ApiSuccess::new("Preferences retrieved successfully", preferences))
+}
+
+/// Update notification preferences (instant emails or hourly/daily digests)
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(preferences): Json<NotificationPreferences>,
+) -> Result<ApiSuccess<NotificationPreferences>, AppError> {
+    let user_service = UserService::new(state.clone());
+    let preferences = user_service.update_preferences(&claims.id, preferences).await?;
+
+    Ok(ApiSuccess::new("Preferences updated successfully", preferences))
+}
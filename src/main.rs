@@ -9,11 +9,11 @@ mod routes;
 mod services;
 mod utils;
 
-use config::{AppConfig, AppState, DatabaseConfig, RedisConfig};
-use middleware::setup_logging;
+use config::{AdminConfig, AppConfig, AppState, BrokerAuthConfig, DatabaseConfig, EmailConfig, MqttConfig, RedisConfig};
+use middleware::{request_span, setup_logging};
 use queue::{QueueConfig, QueueManager};
 use routes::create_router;
-use services::{RedisService, EmailService};
+use services::{EmailTemplate, EmailService, EmailTemplates, Mailer, MqttService, RedisService, TelemetryService};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
@@ -28,6 +28,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let app_config = AppConfig::from_env()?;
     let db_config = DatabaseConfig::from_env()?;
     let redis_config = RedisConfig::from_env()?;
+    let email_config = EmailConfig::from_env()?;
+    let mqtt_config = MqttConfig::from_env()?;
+    let broker_auth_config = BrokerAuthConfig::from_env()?;
+    let admin_config = AdminConfig::from_env();
 
     tracing::info!("Loaded configuration for environment: {}", app_config.environment);
 
@@ -36,9 +40,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Database connection pool created");
 
     // Create Redis service
-    let redis_service = RedisService::new().await?;
+    let redis_service = RedisService::new(&app_config.environment).await?;
     tracing::info!("Redis service created");
 
+    // Create mailer (transport selected by EMAIL_TRANSPORT)
+    let mailer = Mailer::from_config(&email_config)?;
+
+    // Load and validate email templates (fails startup if any kind is missing)
+    let email_templates = EmailTemplates::load(&email_config, &app_config.app_name, &EmailTemplate::names())?;
+
+    // Connect to the MQTT broker if enabled (an unreachable broker degrades instead of failing startup)
+    let mqtt_service = if mqtt_config.enabled {
+        Some(MqttService::from_config(mqtt_config).await?)
+    } else {
+        tracing::info!("MQTT disabled (set MQTT_ENABLED=true to connect)");
+        None
+    };
+
     // Initialize Queue Manager
     let redis_url = redis_config.build_redis_url();
     let queue_config = QueueConfig::new(redis_url, app_config.environment.clone());
@@ -46,21 +64,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Queue manager initialized");
 
     // Create AppState
-    let app_state = AppState::new(db_pool, redis_service, app_config.clone());
+    let app_state = AppState::new(
+        db_pool,
+        redis_service,
+        mailer,
+        email_templates,
+        email_config,
+        mqtt_service,
+        broker_auth_config,
+        admin_config,
+        app_config.clone(),
+    );
 
     // Initialize services (they auto-start their queue processors)
-    let _email_service = EmailService::new(app_state.clone());
+    let email_service = EmailService::new(app_state.clone());
+    email_service.register_queue_hooks();
+    email_service.start_digest_flusher();
+    TelemetryService::new(app_state.clone()).start().await?;
+    app_state.realtime.start();
+    app_state.realtime.forward_job_events();
+    if let Some(mqtt) = &app_state.mqtt {
+        app_state.realtime.forward_mqtt(mqtt).await?;
+    }
     tracing::info!("Services initialized with automatic queue processing");
 
     // Create router
-    let app = create_router(app_state)
+    let app = create_router(app_state.clone())
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods(Any)
                 .allow_headers(Any),
         )
-        .layer(TraceLayer::new_for_http());
+        .layer(TraceLayer::new_for_http().make_span_with(request_span));
 
     // Create server address
     let addr = app_config.server_address();
@@ -76,7 +112,47 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         addr
     );
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Release external connections once in-flight requests have finished
+    if let Some(mqtt) = &app_state.mqtt {
+        if let Err(e) = mqtt.disconnect().await {
+            tracing::warn!("Failed to disconnect MQTT cleanly: {}", e);
+        }
+    }
+
+    tracing::info!("Server stopped");
 
     Ok(())
 }
+
+/// Resolve on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => tracing::error!("Failed to listen for SIGTERM: {}", e),
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received, stopping server...");
+}
@@ -1,16 +1,20 @@
 use axum::{
-    extract::{Request, State},
+    extract::{Query, Request, State},
     middleware::Next,
-    response::Response,
-    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    http::{header, HeaderMap, StatusCode},
 };
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use chrono::{Duration, Utc};
 
+use crate::config::AppState;
 use crate::interceptors::AppError;
 
+/// Cookie holding the admin token for pages opened in a browser
+pub const ADMIN_COOKIE: &str = "admin_token";
+
 /// JWT Claims structure - contains user id and email
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -115,6 +119,80 @@ impl JwtMiddleware {
         mut request: Request,
         next: Next,
     ) -> Result<Response, AppError> {
+        let claims = Self::claims_from_request(&request)?;
+
+        // Add claims to request extensions for handlers to use
+        request.extensions_mut().insert(claims);
+
+        // Continue to next middleware/handler
+        Ok(next.run(request).await)
+    }
+
+    /// Admin middleware - requires a valid token whose user ID is in ADMIN_USER_IDS
+    pub async fn admin(
+        State(state): State<AppState>,
+        mut request: Request,
+        next: Next,
+    ) -> Result<Response, AppError> {
+        let claims = Self::claims_from_request(&request)?;
+        Self::require_admin(&state, &claims)?;
+
+        request.extensions_mut().insert(claims);
+
+        Ok(next.run(request).await)
+    }
+
+    /// Admin middleware for HTML pages opened in a browser
+    ///
+    /// Browsers can't send a Bearer header when navigating or refreshing, so the page is opened
+    /// once with `?token=<jwt>`: the token is moved into an HttpOnly cookie scoped to the page and
+    /// the browser is redirected to the URL without it. A Bearer header still works as well.
+    pub async fn admin_page(
+        State(state): State<AppState>,
+        mut request: Request,
+        next: Next,
+    ) -> Result<Response, AppError> {
+        let query: Query<TokenQuery> = Query::try_from_uri(request.uri())
+            .map_err(|_| AppError::BadRequest("Invalid query string".to_string()))?;
+
+        if let Some(token) = query.0.token {
+            let claims = verify_token(&token)?;
+            Self::require_admin(&state, &claims)?;
+
+            let path = request.uri().path().to_string();
+            let secure = if state.config.public_url.starts_with("https://") { "; Secure" } else { "" };
+            let cookie = format!(
+                "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite=Strict{}",
+                ADMIN_COOKIE,
+                token,
+                path,
+                (claims.exp - Utc::now().timestamp()).max(0),
+                secure,
+            );
+
+            return Ok(([(header::SET_COOKIE, cookie)], Redirect::to(&path)).into_response());
+        }
+
+        let claims = match cookie_token(request.headers()) {
+            Some(token) if !request.headers().contains_key(header::AUTHORIZATION) => verify_token(token)?,
+            _ => Self::claims_from_request(&request)?,
+        };
+        Self::require_admin(&state, &claims)?;
+
+        request.extensions_mut().insert(claims);
+
+        Ok(next.run(request).await)
+    }
+
+    fn require_admin(state: &AppState, claims: &Claims) -> Result<(), AppError> {
+        if !state.admin.is_admin(&claims.id) {
+            return Err(AppError::Forbidden("Admin access required".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Extract and verify the bearer token from the Authorization header
+    fn claims_from_request(request: &Request) -> Result<Claims, AppError> {
         // Extract token from Authorization header
         let auth_header = request
             .headers()
@@ -131,14 +209,25 @@ impl JwtMiddleware {
         let token = auth_header.trim_start_matches("Bearer ");
 
         // Verify token
-        let claims = verify_token(token)?;
+        verify_token(token)
+    }
+}
 
-        // Add claims to request extensions for handlers to use
-        request.extensions_mut().insert(claims);
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
 
-        // Continue to next middleware/handler
-        Ok(next.run(request).await)
-    }
+/// The admin token from the `Cookie` header, if any
+fn cookie_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == ADMIN_COOKIE)
+        .map(|(_, token)| token)
 }
 
 // Helper to extract claims from request extensions in handlers
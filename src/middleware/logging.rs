@@ -1,5 +1,10 @@
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use axum::http::{Request, Uri};
+use tracing::Span;
+
+/// Query parameters carrying credentials (dashboard `?token=`, broker `?secret=`)
+const REDACTED_QUERY_PARAMS: [&str; 2] = ["token", "secret"];
 
 /// Setup logging with file and console output
 pub fn setup_logging() {
@@ -45,3 +50,45 @@ pub fn setup_logging() {
 
     tracing::info!("Logging initialized with level: {}", log_level);
 }
+
+/// Request span for `TraceLayer` that keeps credentials in the query string out of the logs
+pub fn request_span<B>(request: &Request<B>) -> Span {
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        uri = %redact_query(request.uri()),
+        version = ?request.version(),
+    )
+}
+
+/// Replace the values of `REDACTED_QUERY_PARAMS` in the URI with `REDACTED`
+fn redact_query(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if REDACTED_QUERY_PARAMS.contains(&key) => format!("{}=REDACTED", key),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{}", uri.path(), query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_query_hides_credentials() {
+        let uri: Uri = "/api/admin/queues?token=abc.def&page=2&secret=s3cret".parse().unwrap();
+        assert_eq!(redact_query(&uri), "/api/admin/queues?token=REDACTED&page=2&secret=REDACTED");
+
+        let uri: Uri = "/health".parse().unwrap();
+        assert_eq!(redact_query(&uri), "/health");
+    }
+}
@@ -2,4 +2,4 @@ pub mod auth;
 pub mod logging;
 
 pub use auth::{JwtMiddleware, Claims, verify_token, generate_token};
-pub use logging::setup_logging;
+pub use logging::{request_span, setup_logging};
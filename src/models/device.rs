@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::dto::DeviceResponse;
+
+/// IoT device (database entity)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Device {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    /// SHA-256 of the device key
+    pub key_hash: String,
+    pub is_active: bool,
+    /// Time of the last accepted telemetry message
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Device {
+    /// Create a new device
+    pub fn new(owner_id: String, name: String, key_hash: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            owner_id,
+            name,
+            key_hash,
+            is_active: true,
+            last_seen_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Convert to response (without the key hash)
+    pub fn to_response(&self) -> DeviceResponse {
+        DeviceResponse {
+            id: self.id.clone(),
+            owner_id: self.owner_id.clone(),
+            name: self.name.clone(),
+            is_active: self.is_active,
+            last_seen_at: self.last_seen_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// One telemetry reading
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TelemetryReading {
+    pub device_id: String,
+    pub metric: String,
+    pub value: f64,
+    pub recorded_at: DateTime<Utc>,
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Final outcome of an email job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailLogStatus {
+    /// Accepted by the transport
+    Sent,
+    /// Failed after all retries
+    Failed,
+    /// Skipped because the recipient is suppressed
+    Suppressed,
+}
+
+impl EmailLogStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailLogStatus::Sent => "sent",
+            EmailLogStatus::Failed => "failed",
+            EmailLogStatus::Suppressed => "suppressed",
+        }
+    }
+}
+
+/// Email delivery log entry (database entity)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmailLog {
+    pub id: String,
+    pub job_id: String,
+    pub recipient: String,
+    pub kind: String,
+    pub locale: String,
+    pub template_version: Option<String>,
+    pub status: String,
+    pub message_id: Option<String>,
+    pub provider_response: Option<String>,
+    pub error: Option<String>,
+    pub attempts: i32,
+    /// When the job was enqueued (unknown for jobs that failed permanently)
+    pub queued_at: Option<DateTime<Utc>>,
+    /// When the outcome was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+impl EmailLog {
+    /// Create a log entry for a job outcome (delivery details are filled in by the caller)
+    pub fn new(job_id: &str, recipient: &str, kind: &str, locale: &str, status: EmailLogStatus) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            job_id: job_id.to_string(),
+            recipient: recipient.to_string(),
+            kind: kind.to_string(),
+            locale: locale.to_string(),
+            template_version: None,
+            status: status.as_str().to_string(),
+            message_id: None,
+            provider_response: None,
+            error: None,
+            attempts: 0,
+            queued_at: None,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Why an address is suppressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressionReason {
+    /// Hard bounce reported by the provider
+    Bounce,
+    /// Recipient marked an email as spam
+    Complaint,
+    /// Recipient opted out of notification emails
+    Unsubscribe,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuppressionReason::Bounce => "bounce",
+            SuppressionReason::Complaint => "complaint",
+            SuppressionReason::Unsubscribe => "unsubscribe",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bounce" => Some(SuppressionReason::Bounce),
+            "complaint" => Some(SuppressionReason::Complaint),
+            "unsubscribe" => Some(SuppressionReason::Unsubscribe),
+            _ => None,
+        }
+    }
+
+    /// Whether this reason blocks every email, including transactional ones
+    pub fn blocks_all(&self) -> bool {
+        !matches!(self, SuppressionReason::Unsubscribe)
+    }
+}
+
+/// Suppressed address (database entity)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmailSuppression {
+    pub email: String,
+    pub reason: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl EmailSuppression {
+    pub fn reason(&self) -> Option<SuppressionReason> {
+        SuppressionReason::parse(&self.reason)
+    }
+}
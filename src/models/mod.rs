@@ -1,3 +1,9 @@
 pub mod user;
+pub mod email_suppression;
+pub mod email_log;
+pub mod device;
 
-pub use user::User;
+pub use user::{NotificationDelivery, User};
+pub use email_suppression::{EmailSuppression, SuppressionReason};
+pub use email_log::{EmailLog, EmailLogStatus};
+pub use device::{Device, TelemetryReading};
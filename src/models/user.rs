@@ -5,6 +5,37 @@ use uuid::Uuid;
 
 use crate::dto::UserResponse;
 
+/// How a user receives notification emails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationDelivery {
+    /// One email per notification
+    Instant,
+    /// Collected and sent as one digest at the top of every hour
+    Hourly,
+    /// Collected and sent as one digest per day
+    Daily,
+}
+
+impl NotificationDelivery {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationDelivery::Instant => "instant",
+            NotificationDelivery::Hourly => "hourly",
+            NotificationDelivery::Daily => "daily",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "instant" => Some(NotificationDelivery::Instant),
+            "hourly" => Some(NotificationDelivery::Hourly),
+            "daily" => Some(NotificationDelivery::Daily),
+            _ => None,
+        }
+    }
+}
+
 /// User model (database entity)
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -12,6 +43,8 @@ pub struct User {
     pub email: String,
     pub password_hash: String,
     pub name: Option<String>,
+    /// Preferred locale for emails (e.g. "en", "vi")
+    pub locale: String,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -19,13 +52,14 @@ pub struct User {
 
 impl User {
     /// Create a new user
-    pub fn new(email: String, password_hash: String, name: Option<String>) -> Self {
+    pub fn new(email: String, password_hash: String, name: Option<String>, locale: String) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
             email,
             password_hash,
             name,
+            locale,
             is_active: true,
             created_at: now,
             updated_at: now,
@@ -38,6 +72,7 @@ impl User {
             id: self.id.clone(),
             email: self.email.clone(),
             name: self.name.clone(),
+            locale: self.locale.clone(),
             is_active: self.is_active,
             created_at: self.created_at,
             updated_at: self.updated_at,
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Batch processor settings
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum number of jobs handed to the handler at once
+    pub max_size: usize,
+    /// Maximum time to wait for a batch to fill after the first job arrives
+    pub max_wait: Duration,
+}
+
+impl BatchOptions {
+    pub fn new(max_size: usize, max_wait: Duration) -> Self {
+        Self {
+            max_size: max_size.max(1),
+            max_wait,
+        }
+    }
+}
+
+/// Per-job result of a batch handler
+///
+/// Jobs are acknowledged (succeeded) unless explicitly failed.
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    failures: HashMap<String, String>,
+}
+
+impl BatchOutcome {
+    /// Acknowledge every job in the batch
+    pub fn success() -> Self {
+        Self::default()
+    }
+
+    /// Mark a job as failed (it will be retried or moved to the failed list)
+    pub fn fail(&mut self, job_id: impl Into<String>, error: impl Into<String>) {
+        self.failures.insert(job_id.into(), error.into());
+    }
+
+    /// Builder variant of [`BatchOutcome::fail`]
+    pub fn with_failure(mut self, job_id: impl Into<String>, error: impl Into<String>) -> Self {
+        self.fail(job_id, error);
+        self
+    }
+
+    /// Error for a job, if it was failed
+    pub fn error_for(&self, job_id: &str) -> Option<&str> {
+        self.failures.get(job_id).map(String::as_str)
+    }
+}
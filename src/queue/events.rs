@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Lifecycle stage of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueEventType {
+    Enqueued,
+    Started,
+    Completed,
+    Failed,
+    Retrying,
+    Stalled,
+}
+
+/// Event-specific details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum QueueEventKind {
+    Enqueued,
+    Started { attempt: u32 },
+    Completed { attempt: u32, duration_ms: u64 },
+    /// Job failed permanently (no retries left)
+    Failed { attempts: u32, error: String },
+    Retrying { attempt: u32, delay_ms: u64, error: String },
+    /// Job was found in processing past its timeout and moved back to waiting
+    Stalled,
+}
+
+impl QueueEventKind {
+    pub fn event_type(&self) -> QueueEventType {
+        match self {
+            QueueEventKind::Enqueued => QueueEventType::Enqueued,
+            QueueEventKind::Started { .. } => QueueEventType::Started,
+            QueueEventKind::Completed { .. } => QueueEventType::Completed,
+            QueueEventKind::Failed { .. } => QueueEventType::Failed,
+            QueueEventKind::Retrying { .. } => QueueEventType::Retrying,
+            QueueEventKind::Stalled => QueueEventType::Stalled,
+        }
+    }
+}
+
+/// Job lifecycle event (published locally and on Redis pub/sub)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEvent {
+    pub queue: String,
+    pub job_id: String,
+    /// User the job runs for, if it was added with `add_to_queue_for_user`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Instance that emitted the event
+    pub instance_id: String,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub kind: QueueEventKind,
+    /// Job payload, attached to `failed` events on the instance that emitted them
+    ///
+    /// Never serialized: payloads can hold secrets (reset tokens, attachments), so events
+    /// received from other instances over Redis come without it.
+    #[serde(skip)]
+    pub data: Option<serde_json::Value>,
+}
+
+impl QueueEvent {
+    pub fn event_type(&self) -> QueueEventType {
+        self.kind.event_type()
+    }
+
+    /// Deserialize the attached job payload
+    pub fn data_as<T: for<'de> Deserialize<'de>>(&self) -> Option<T> {
+        self.data
+            .as_ref()
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// Async callback invoked for matching events
+pub type QueueHook =
+    Arc<dyn Fn(QueueEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Registered hook with its filters
+#[derive(Clone)]
+pub(crate) struct HookEntry {
+    pub queue: Option<String>,
+    pub event_type: QueueEventType,
+    pub hook: QueueHook,
+}
+
+impl HookEntry {
+    pub fn matches(&self, event: &QueueEvent) -> bool {
+        self.event_type == event.event_type()
+            && self.queue.as_deref().is_none_or(|queue| queue == event.queue)
+    }
+}
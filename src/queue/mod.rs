@@ -1,5 +1,11 @@
 mod queue_service;
 mod job;
+mod events;
+mod batch;
+mod options;
 
 pub use queue_service::{QueueService, QueueManager, QueueJob, QueueConfig, QueueStats};
 pub use job::{Job, JobId, JobStatus, JobResult};
+pub use events::{QueueEvent, QueueEventKind, QueueEventType, QueueHook};
+pub use options::{QueueOptions, RetentionPolicy};
+pub use batch::{BatchOptions, BatchOutcome};
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+/// Default job timeout (60 seconds)
+pub const DEFAULT_JOB_TIMEOUT_MS: u64 = 60_000;
+/// Default TTL of `:job:<id>` keys (24 hours)
+pub const DEFAULT_JOB_TTL_SECS: u64 = 86_400;
+/// Default base delay for exponential retry backoff (2 seconds)
+pub const DEFAULT_BACKOFF_MS: u64 = 2_000;
+
+/// What to do with finished jobs (`:succeeded` / `:failed` lists)
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Drop finished jobs immediately instead of keeping them in the list
+    pub remove: bool,
+    /// Keep at most this many jobs
+    pub keep_last: Option<usize>,
+    /// Keep jobs for at most this long after they finished
+    pub keep_for: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// Remove jobs as soon as they finish
+    pub fn remove() -> Self {
+        Self {
+            remove: true,
+            ..Default::default()
+        }
+    }
+
+    /// Keep every finished job (lists grow without limit)
+    pub fn keep_all() -> Self {
+        Self::default()
+    }
+
+    /// Keep the last `count` jobs
+    pub fn keep_last(count: usize) -> Self {
+        Self {
+            keep_last: Some(count),
+            ..Default::default()
+        }
+    }
+
+    /// Keep jobs for `duration` after they finished
+    pub fn keep_for(duration: Duration) -> Self {
+        Self {
+            keep_for: Some(duration),
+            ..Default::default()
+        }
+    }
+
+    /// Additionally limit the number of kept jobs
+    pub fn with_keep_last(mut self, count: usize) -> Self {
+        self.keep_last = Some(count);
+        self
+    }
+
+    /// Additionally limit how long jobs are kept
+    pub fn with_keep_for(mut self, duration: Duration) -> Self {
+        self.keep_for = Some(duration);
+        self
+    }
+
+    pub(crate) fn from_remove_flag(remove: bool) -> Self {
+        if remove {
+            Self::remove()
+        } else {
+            Self::keep_all()
+        }
+    }
+}
+
+/// Per-queue options
+#[derive(Debug, Clone)]
+pub struct QueueOptions {
+    pub max_retries: u32,
+    /// Handler timeout per attempt
+    pub job_timeout_ms: u64,
+    /// TTL of the `:job:<id>` key written at enqueue time
+    pub job_ttl_secs: u64,
+    /// Base delay for exponential retry backoff
+    pub backoff_ms: u64,
+    /// Retention of the `:succeeded` list
+    pub on_success: RetentionPolicy,
+    /// Retention of the `:failed` list
+    pub on_failure: RetentionPolicy,
+}
+
+impl QueueOptions {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            job_timeout_ms: DEFAULT_JOB_TIMEOUT_MS,
+            job_ttl_secs: DEFAULT_JOB_TTL_SECS,
+            backoff_ms: DEFAULT_BACKOFF_MS,
+            on_success: RetentionPolicy::remove(),
+            on_failure: RetentionPolicy::keep_all(),
+        }
+    }
+
+    pub fn with_job_timeout(mut self, timeout: Duration) -> Self {
+        self.job_timeout_ms = timeout.as_millis() as u64;
+        self
+    }
+
+    pub fn with_job_ttl(mut self, ttl: Duration) -> Self {
+        self.job_ttl_secs = ttl.as_secs();
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff_ms = backoff.as_millis() as u64;
+        self
+    }
+
+    pub fn with_success_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.on_success = policy;
+        self
+    }
+
+    pub fn with_failure_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.on_failure = policy;
+        self
+    }
+}
@@ -1,16 +1,47 @@
 use anyhow::{Context, Result};
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use redis::{aio::ConnectionManager, AsyncCommands};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::time::{sleep, timeout, Duration};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+use tokio::time::{sleep, timeout, Duration, Instant};
 use uuid::Uuid;
 
 use crate::interceptors::AppError;
+use super::batch::{BatchOptions, BatchOutcome};
+use super::events::{HookEntry, QueueEvent, QueueEventKind, QueueEventType};
+use super::options::{QueueOptions, RetentionPolicy, DEFAULT_BACKOFF_MS};
+
+/// How often workers run maintenance (stalled job recovery, retention trimming)
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+/// Extra time past a job's timeout before it is considered stalled
+const STALLED_GRACE_MS: i64 = 5000;
 
 // Global queue manager
 static QUEUE_MANAGER: OnceCell<QueueManager> = OnceCell::new();
 
+/// Moves due jobs from the delayed set back to the waiting list
+static PROMOTE_DELAYED_SCRIPT: Lazy<redis::Script> = Lazy::new(|| {
+    redis::Script::new(
+        r"
+        local jobs = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, 100)
+        for _, job in ipairs(jobs) do
+            redis.call('ZREM', KEYS[1], job)
+            redis.call('LPUSH', KEYS[2], job)
+        end
+        return #jobs
+        ",
+    )
+});
+
+/// Window used to compute queue throughput
+const THROUGHPUT_WINDOW_MINUTES: i64 = 5;
+/// How long per-minute completion counters are kept
+const METRICS_TTL_SECS: i64 = 3600;
+
 /// Job structure for queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueJob<T>
@@ -24,6 +55,12 @@ where
     pub timeout_ms: u64,
     pub backoff_ms: u64,
     pub created_at: i64,
+    /// Set when the job lands in the succeeded or failed list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>,
+    /// User the job runs for; copied into its events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
 }
 
 impl<T> QueueJob<T>
@@ -37,10 +74,22 @@ where
             attempts: 0,
             max_retries,
             timeout_ms,
-            backoff_ms: 2000, // 2 seconds base
+            backoff_ms: DEFAULT_BACKOFF_MS,
             created_at: chrono::Utc::now().timestamp(),
+            finished_at: None,
+            user_id: None,
         }
     }
+
+    pub fn with_backoff(mut self, backoff_ms: u64) -> Self {
+        self.backoff_ms = backoff_ms;
+        self
+    }
+
+    pub fn with_user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
 }
 
 /// Queue configuration
@@ -48,7 +97,9 @@ where
 pub struct QueueConfig {
     pub redis_url: String,
     pub environment: String,
+    /// Default success retention for queues created without explicit options
     pub remove_on_success: bool,
+    /// Default failure retention for queues created without explicit options
     pub remove_on_failure: bool,
 }
 
@@ -66,10 +117,18 @@ impl QueueConfig {
 /// Queue statistics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueueStats {
+    pub name: String,
     pub waiting: usize,
     pub processing: usize,
     pub succeeded: usize,
     pub failed: usize,
+    /// Jobs waiting for their retry backoff to elapse
+    pub delayed: usize,
+    pub paused: bool,
+    /// Completed jobs per minute over the last few minutes
+    pub throughput_per_minute: f64,
+    /// Age of the next job to be processed, in seconds
+    pub oldest_waiting_secs: Option<i64>,
 }
 
 /// Global Queue Manager
@@ -77,6 +136,11 @@ pub struct QueueStats {
 pub struct QueueManager {
     config: Arc<QueueConfig>,
     client: redis::Client,
+    instance_id: String,
+    events: broadcast::Sender<QueueEvent>,
+    hooks: Arc<RwLock<Vec<HookEntry>>>,
+    publisher: Arc<tokio::sync::OnceCell<ConnectionManager>>,
+    queues: Arc<RwLock<BTreeSet<String>>>,
 }
 
 impl QueueManager {
@@ -85,14 +149,24 @@ impl QueueManager {
         let client = redis::Client::open(config.redis_url.as_str())
             .map_err(|e| AppError::RedisError(format!("Failed to create Redis client: {}", e)))?;
 
+        let (events, _) = broadcast::channel(1024);
+
         let manager = QueueManager {
             config: Arc::new(config),
             client,
+            instance_id: Uuid::new_v4().to_string(),
+            events,
+            hooks: Arc::new(RwLock::new(Vec::new())),
+            publisher: Arc::new(tokio::sync::OnceCell::new()),
+            queues: Arc::new(RwLock::new(BTreeSet::new())),
         };
 
         QUEUE_MANAGER
-            .set(manager)
+            .set(manager.clone())
             .map_err(|_| AppError::RedisError("Queue manager already initialized".to_string()))?;
+
+        // Relay events published by other instances
+        manager.spawn_event_listener();
         Ok(())
     }
 
@@ -105,15 +179,100 @@ impl QueueManager {
 
     /// Create a queue service instance (Legacy method)
     pub fn create_queue(&self, name: &str, max_retries: u32) -> QueueService {
-        let queue_name = format!("{}_{}_queue", self.config.environment, name);
+        let options = QueueOptions::new(max_retries)
+            .with_success_retention(RetentionPolicy::from_remove_flag(self.config.remove_on_success))
+            .with_failure_retention(RetentionPolicy::from_remove_flag(self.config.remove_on_failure));
+
+        self.create_queue_with_options(name, options)
+    }
+
+    /// Create a queue service instance with per-queue options
+    pub fn create_queue_with_options(&self, name: &str, options: QueueOptions) -> QueueService {
+        self.register_queue(name);
 
         QueueService {
-            queue_name,
-            max_retries,
+            name: name.to_string(),
+            queue_name: self.full_queue_name(name),
+            options: Arc::new(options),
             manager: self.clone(),
         }
     }
 
+    /// Get a handle to a registered queue by name (no worker is started)
+    pub async fn find_queue(&self, name: &str) -> Result<QueueService, AppError> {
+        if !self.list_queues().await?.iter().any(|queue| queue == name) {
+            return Err(AppError::NotFound(format!("Queue '{}' not found", name)));
+        }
+
+        Ok(self.create_queue(name, 0))
+    }
+
+    /// Names of all queues created on any instance
+    pub async fn list_queues(&self) -> Result<Vec<String>, AppError> {
+        let mut names = self
+            .queues
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        let mut conn = self.get_connection().await?;
+        let remote: Vec<String> = conn.smembers(self.registry_key()).await?;
+        names.extend(remote);
+
+        Ok(names.into_iter().collect())
+    }
+
+    /// Stats for every registered queue
+    pub async fn get_all_stats(&self) -> Result<Vec<QueueStats>, AppError> {
+        if !self.health_check().await? {
+            return Err(AppError::RedisError("Redis is not available. Cannot get queue stats.".to_string()));
+        }
+
+        let mut stats = Vec::new();
+        for name in self.list_queues().await? {
+            stats.push(self.get_stats(&name).await?);
+        }
+
+        Ok(stats)
+    }
+
+    /// Record a queue name locally and in the shared Redis registry
+    fn register_queue(&self, name: &str) {
+        let inserted = self
+            .queues
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string());
+
+        if !inserted {
+            return;
+        }
+
+        let manager = self.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let result = async {
+                let mut conn = manager.get_connection().await?;
+                conn.sadd::<_, _, ()>(manager.registry_key(), &name).await?;
+                Ok::<(), AppError>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                tracing::warn!("Failed to register queue '{}': {}", name, e);
+            }
+        });
+    }
+
+    /// Redis set holding the names of all queues
+    fn registry_key(&self) -> String {
+        format!("{}_queues", self.config.environment)
+    }
+
+    fn full_queue_name(&self, name: &str) -> String {
+        format!("{}_{}_queue", self.config.environment, name)
+    }
+
     /// Create a queue service instance with processor function (Optimized)
     pub fn create_queue_with_processor<T, F, Fut>(
         &self,
@@ -126,13 +285,7 @@ impl QueueManager {
         F: Fn(QueueJob<T>) -> Fut + Send + Sync + 'static,
         Fut: std::future::Future<Output = Result<(), AppError>> + Send + 'static,
     {
-        let queue_name = format!("{}_{}_queue", self.config.environment, name);
-        
-        let queue_service = QueueService {
-            queue_name: queue_name.clone(),
-            max_retries,
-            manager: self.clone(),
-        };
+        let queue_service = self.create_queue(name, max_retries);
 
         // Automatically start processing (non-blocking)
         queue_service.start_processing::<T, F, Fut>(processor);
@@ -140,6 +293,146 @@ impl QueueManager {
         queue_service
     }
 
+    /// Subscribe to job lifecycle events from all queues and instances
+    pub fn subscribe(&self) -> broadcast::Receiver<QueueEvent> {
+        self.events.subscribe()
+    }
+
+    /// Register a hook for an event type on every queue
+    pub fn on<F, Fut>(&self, event_type: QueueEventType, hook: F)
+    where
+        F: Fn(QueueEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.register_hook(None, event_type, hook);
+    }
+
+    fn register_hook<F, Fut>(&self, queue: Option<String>, event_type: QueueEventType, hook: F)
+    where
+        F: Fn(QueueEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let entry = HookEntry {
+            queue,
+            event_type,
+            hook: Arc::new(move |event| Box::pin(hook(event))),
+        };
+
+        self.hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(entry);
+    }
+
+    /// Redis pub/sub channel for queue events
+    fn events_channel(&self) -> String {
+        format!("{}_queue_events", self.config.environment)
+    }
+
+    /// Emit an event locally (broadcast + hooks) and publish it to other instances
+    async fn emit(
+        &self,
+        queue_name: &str,
+        job_id: &str,
+        user_id: Option<&str>,
+        kind: QueueEventKind,
+        data: Option<serde_json::Value>,
+    ) {
+        let event = QueueEvent {
+            queue: queue_name.to_string(),
+            job_id: job_id.to_string(),
+            user_id: user_id.map(str::to_string),
+            instance_id: self.instance_id.clone(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            kind,
+            data,
+        };
+
+        tracing::debug!("Queue event {:?} for job {} in queue '{}'", event.event_type(), job_id, queue_name);
+
+        // No receivers is not an error
+        let _ = self.events.send(event.clone());
+
+        // Hooks only run on the instance that produced the event
+        let hooks: Vec<HookEntry> = self
+            .hooks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|entry| entry.matches(&event))
+            .cloned()
+            .collect();
+
+        for entry in hooks {
+            tokio::spawn((entry.hook)(event.clone()));
+        }
+
+        if let Err(e) = self.publish_event(&event).await {
+            tracing::warn!("Failed to publish queue event for job {}: {}", job_id, e);
+        }
+    }
+
+    async fn publish_event(&self, event: &QueueEvent) -> Result<(), AppError> {
+        let payload = serde_json::to_string(event)?;
+        let channel = self.events_channel();
+
+        timeout(Duration::from_secs(2), async {
+            let conn = self
+                .publisher
+                .get_or_try_init(|| self.get_connection())
+                .await?;
+            conn.clone().publish::<_, _, ()>(&channel, &payload).await?;
+            Ok::<(), AppError>(())
+        })
+        .await
+        .map_err(|_| AppError::RedisError("Timeout publishing queue event".to_string()))?
+    }
+
+    /// Forward events from other instances to local subscribers
+    fn spawn_event_listener(&self) {
+        let manager = self.clone();
+
+        tokio::spawn(async move {
+            let channel = manager.events_channel();
+
+            loop {
+                let mut pubsub = match manager.client.get_async_pubsub().await {
+                    Ok(p) => p,
+                    Err(e) => {
+                        tracing::warn!("Queue event listener failed to connect: {}", e);
+                        sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                };
+
+                if let Err(e) = pubsub.subscribe(&channel).await {
+                    tracing::warn!("Queue event listener failed to subscribe to '{}': {}", channel, e);
+                    sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let payload: String = match msg.get_payload() {
+                        Ok(p) => p,
+                        Err(_) => continue,
+                    };
+
+                    match serde_json::from_str::<QueueEvent>(&payload) {
+                        Ok(event) if event.instance_id != manager.instance_id => {
+                            let _ = manager.events.send(event);
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::debug!("Ignoring malformed queue event: {}", e),
+                    }
+                }
+
+                tracing::warn!("Queue event listener disconnected, reconnecting in 5 seconds...");
+                sleep(Duration::from_secs(5)).await;
+            }
+        });
+    }
+
     /// Create a connection with timeout
     async fn get_connection(&self) -> Result<ConnectionManager, AppError> {
         let connection_future = ConnectionManager::new(self.client.clone());
@@ -163,7 +456,9 @@ impl QueueManager {
     }
 
     /// Get queue statistics with timeout
-    async fn get_stats(&self, queue_name: &str) -> Result<QueueStats, AppError> {
+    async fn get_stats(&self, name: &str) -> Result<QueueStats, AppError> {
+        let queue_name = self.full_queue_name(name);
+
         let result = timeout(Duration::from_secs(3), async {
             let mut conn = self.get_connection().await?;
 
@@ -171,17 +466,39 @@ impl QueueManager {
             let processing_key = format!("{}:processing", queue_name);
             let succeeded_key = format!("{}:succeeded", queue_name);
             let failed_key = format!("{}:failed", queue_name);
+            let delayed_key = format!("{}:delayed", queue_name);
 
             let waiting: usize = conn.llen(&waiting_key).await.unwrap_or(0);
             let processing: usize = conn.llen(&processing_key).await.unwrap_or(0);
             let succeeded: usize = conn.llen(&succeeded_key).await.unwrap_or(0);
             let failed: usize = conn.llen(&failed_key).await.unwrap_or(0);
+            let delayed: usize = conn.zcard(&delayed_key).await.unwrap_or(0);
+            let paused: bool = conn.exists(format!("{}:paused", queue_name)).await.unwrap_or(false);
+
+            // Next job to be processed sits at the tail of the waiting list
+            let oldest: Option<String> = conn.lindex(&waiting_key, -1).await.unwrap_or(None);
+            let oldest_waiting_secs = oldest
+                .and_then(|job_json| serde_json::from_str::<QueueJob<serde_json::Value>>(&job_json).ok())
+                .map(|job| (chrono::Utc::now().timestamp() - job.created_at).max(0));
+
+            let current_minute = chrono::Utc::now().timestamp() / 60;
+            let metric_keys: Vec<String> = (0..THROUGHPUT_WINDOW_MINUTES)
+                .map(|offset| Self::completed_metric_key(&queue_name, current_minute - offset))
+                .collect();
+            let counts: Vec<Option<u64>> = conn.mget(&metric_keys).await.unwrap_or_default();
+            let completed: u64 = counts.into_iter().flatten().sum();
+            let throughput_per_minute = completed as f64 / THROUGHPUT_WINDOW_MINUTES as f64;
 
             Ok::<QueueStats, AppError>(QueueStats {
+                name: name.to_string(),
                 waiting,
                 processing,
                 succeeded,
                 failed,
+                delayed,
+                paused,
+                throughput_per_minute,
+                oldest_waiting_secs,
             })
         }).await;
 
@@ -190,19 +507,142 @@ impl QueueManager {
             Err(_) => Err(AppError::RedisError(format!("Timeout getting stats for queue '{}'", queue_name))),
         }
     }
+
+    /// Per-minute counter of completed jobs
+    fn completed_metric_key(queue_name: &str, minute: i64) -> String {
+        format!("{}:metrics:completed:{}", queue_name, minute)
+    }
+}
+
+/// State shared by the steps of a running worker
+struct WorkerContext {
+    manager: QueueManager,
+    queue_name: String,
+    options: Arc<QueueOptions>,
+    waiting_key: String,
+    processing_key: String,
+    active_key: String,
+    paused_key: String,
+    delayed_key: String,
+}
+
+impl WorkerContext {
+    fn new(service: &QueueService) -> Self {
+        let queue_name = service.queue_name.clone();
+
+        Self {
+            manager: service.manager.clone(),
+            options: Arc::clone(&service.options),
+            waiting_key: format!("{}:waiting", queue_name),
+            processing_key: format!("{}:processing", queue_name),
+            active_key: format!("{}:active", queue_name),
+            paused_key: format!("{}:paused", queue_name),
+            delayed_key: format!("{}:delayed", queue_name),
+            queue_name,
+        }
+    }
+
+    /// Run health/maintenance/pause checks and return a connection when the worker may fetch jobs
+    async fn ready_connection(&self, last_maintenance: &mut Instant) -> Option<ConnectionManager> {
+        // Check Redis health before attempting connection
+        if !self.manager.health_check().await.unwrap_or(false) {
+            tracing::warn!("Queue {} Redis health check failed, waiting 10 seconds...", self.queue_name);
+            sleep(Duration::from_secs(10)).await;
+            return None;
+        }
+
+        if last_maintenance.elapsed() >= MAINTENANCE_INTERVAL {
+            *last_maintenance = Instant::now();
+            if let Err(e) = QueueService::recover_stalled(self).await {
+                tracing::warn!("Stalled job check failed for queue '{}': {}", self.queue_name, e);
+            }
+            if let Err(e) = QueueService::apply_retention(&self.manager, &self.queue_name, &self.options).await {
+                tracing::warn!("Retention trimming failed for queue '{}': {}", self.queue_name, e);
+            }
+        }
+
+        let mut conn = match self.manager.get_connection().await {
+            Ok(c) => c,
+            Err(_) => {
+                sleep(Duration::from_secs(5)).await;
+                return None;
+            }
+        };
+
+        // Paused queues keep their waiting jobs until resumed
+        let paused: bool = conn.exists(&self.paused_key).await.unwrap_or(false);
+        if paused {
+            sleep(Duration::from_secs(1)).await;
+            return None;
+        }
+
+        // Retries whose backoff elapsed go back to waiting
+        let promoted: Result<usize, _> = PROMOTE_DELAYED_SCRIPT
+            .key(&self.delayed_key)
+            .key(&self.waiting_key)
+            .arg(chrono::Utc::now().timestamp_millis())
+            .invoke_async(&mut conn)
+            .await;
+        if let Ok(count) = promoted {
+            if count > 0 {
+                tracing::debug!("Promoted {} delayed jobs in queue '{}'", count, self.queue_name);
+            }
+        }
+
+        Some(conn)
+    }
+
+    /// Count the attempt, record the start time for stalled job detection and emit `started`
+    async fn mark_started<T>(&self, conn: &mut ConnectionManager, job: &mut QueueJob<T>)
+    where
+        T: Clone,
+    {
+        tracing::debug!("Processing job: {} in queue '{}'", job.id, self.queue_name);
+        job.attempts += 1;
+
+        let started_at = chrono::Utc::now().timestamp_millis();
+        let _: Result<(), _> = conn.hset(&self.active_key, &job.id, started_at).await;
+        self.manager
+            .emit(&self.queue_name, &job.id, job.user_id.as_deref(), QueueEventKind::Started { attempt: job.attempts }, None)
+            .await;
+    }
 }
 
 /// Queue Service - Optimized BeeQueue pattern
 #[derive(Clone)]
 pub struct QueueService {
+    name: String,
     queue_name: String,
-    max_retries: u32,
+    options: Arc<QueueOptions>,
     manager: QueueManager,
 }
 
 impl QueueService {
     /// Add job to queue with fast fail on Redis error
     pub async fn add_to_queue<T>(&self, data: T) -> Result<String, AppError>
+    where
+        T: Serialize + Clone,
+    {
+        self.enqueue(self.new_job(data)).await
+    }
+
+    /// Add a job run on behalf of a user; its completed/failed events are pushed to them
+    pub async fn add_to_queue_for_user<T>(&self, user_id: &str, data: T) -> Result<String, AppError>
+    where
+        T: Serialize + Clone,
+    {
+        self.enqueue(self.new_job(data).with_user(user_id)).await
+    }
+
+    fn new_job<T>(&self, data: T) -> QueueJob<T>
+    where
+        T: Serialize + Clone,
+    {
+        QueueJob::new(data, self.options.max_retries, self.options.job_timeout_ms)
+            .with_backoff(self.options.backoff_ms)
+    }
+
+    async fn enqueue<T>(&self, job: QueueJob<T>) -> Result<String, AppError>
     where
         T: Serialize + Clone,
     {
@@ -211,7 +651,6 @@ impl QueueService {
             return Err(AppError::RedisError("Redis is not available. Job cannot be added to queue.".to_string()));
         }
 
-        let job = QueueJob::new(data, self.max_retries, 60000); // 60s timeout
         let job_id = job.id.clone();
         let job_json = serde_json::to_string(&job)
             .map_err(|e| AppError::QueueError(format!("Failed to serialize job: {}", e)))?;
@@ -220,13 +659,13 @@ impl QueueService {
         let result = timeout(Duration::from_secs(5), async {
             let mut conn = self.manager.get_connection().await?;
 
-            // Store job data with TTL (24 hours)
+            // Store job data with TTL
             let job_key = format!("{}:job:{}", self.queue_name, job_id);
-            conn.set_ex::<_, _, ()>(&job_key, &job_json, 86400).await?;
+            conn.set_ex::<_, _, ()>(&job_key, &job_json, self.options.job_ttl_secs).await?;
 
-            // Push to waiting list
+            // Push to waiting list (workers pop from the tail, so this is FIFO)
             let waiting_key = format!("{}:waiting", self.queue_name);
-            conn.rpush::<_, _, ()>(&waiting_key, &job_json).await?;
+            conn.lpush::<_, _, ()>(&waiting_key, &job_json).await?;
 
             Ok::<(), AppError>(())
         }).await;
@@ -234,6 +673,9 @@ impl QueueService {
         match result {
             Ok(Ok(_)) => {
                 tracing::debug!("Job {} added to queue '{}'", job_id, self.queue_name);
+                self.manager
+                    .emit(&self.queue_name, &job_id, job.user_id.as_deref(), QueueEventKind::Enqueued, None)
+                    .await;
                 Ok(job_id)
             }
             Ok(Err(e)) => Err(e),
@@ -261,6 +703,46 @@ impl QueueService {
         self.start_processing(handler);
     }
 
+    /// Start processing jobs in batches (spawns background worker automatically)
+    ///
+    /// The worker reserves up to `batch.max_size` jobs, waiting at most `batch.max_wait`
+    /// for the batch to fill, then hands them to the handler in one call.
+    pub fn start_batch_processing<T, F, Fut>(&self, batch: BatchOptions, handler: F)
+    where
+        T: for<'de> Deserialize<'de> + Serialize + Clone + Send + Sync + 'static,
+        F: Fn(Vec<QueueJob<T>>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<BatchOutcome, AppError>> + Send + 'static,
+    {
+        self.spawn_batch_worker(batch, handler);
+    }
+
+    /// Register a hook for an event type on this queue
+    pub fn on<F, Fut>(&self, event_type: QueueEventType, hook: F)
+    where
+        F: Fn(QueueEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.manager.register_hook(Some(self.queue_name.clone()), event_type, hook);
+    }
+
+    /// Register a hook for jobs that failed permanently
+    pub fn on_failed<F, Fut>(&self, hook: F)
+    where
+        F: Fn(QueueEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on(QueueEventType::Failed, hook);
+    }
+
+    /// Register a hook for completed jobs
+    pub fn on_completed<F, Fut>(&self, hook: F)
+    where
+        F: Fn(QueueEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on(QueueEventType::Completed, hook);
+    }
+
     /// Internal method to spawn background worker
     fn spawn_worker<T, F, Fut>(&self, handler: F)
     where
@@ -269,33 +751,21 @@ impl QueueService {
         Fut: std::future::Future<Output = Result<(), AppError>> + Send + 'static,
     {
         let handler = Arc::new(handler);
-        let queue_name = self.queue_name.clone();
-        let manager = self.manager.clone();
-        let waiting_key = format!("{}:waiting", queue_name);
-        let processing_key = format!("{}:processing", queue_name);
+        let ctx = WorkerContext::new(self);
 
         tokio::spawn(async move {
-            tracing::info!("🚀 Worker started for queue: {}", queue_name);
+            tracing::info!("🚀 Worker started for queue: {}", ctx.queue_name);
+
+            let mut last_maintenance = Instant::now();
 
             loop {
-                // Check Redis health before attempting connection
-                if !manager.health_check().await.unwrap_or(false) {
-                    tracing::warn!("Queue {} Redis health check failed, waiting 10 seconds...", queue_name);
-                    sleep(Duration::from_secs(10)).await;
+                let Some(mut conn) = ctx.ready_connection(&mut last_maintenance).await else {
                     continue;
-                }
-
-                let mut conn = match manager.get_connection().await {
-                    Ok(c) => c,
-                    Err(_) => {
-                        sleep(Duration::from_secs(5)).await;
-                        continue;
-                    }
                 };
 
                 // Move job from waiting to processing (BRPOPLPUSH with 5s timeout)
                 let result: Result<Option<String>, _> =
-                    conn.brpoplpush(&waiting_key, &processing_key, 5.0).await;
+                    conn.brpoplpush(&ctx.waiting_key, &ctx.processing_key, 5.0).await;
 
                 match result {
                     Ok(Some(job_json)) => {
@@ -304,11 +774,11 @@ impl QueueService {
                             Err(_) => continue,
                         };
 
-                        tracing::debug!("Processing job: {} in queue '{}'", job.id, queue_name);
-                        job.attempts += 1;
+                        ctx.mark_started(&mut conn, &mut job).await;
 
                         let handler_clone = Arc::clone(&handler);
                         let job_clone = job.clone();
+                        let started = Instant::now();
 
                         // Execute handler with timeout
                         let timeout_duration = Duration::from_millis(job.timeout_ms);
@@ -320,19 +790,21 @@ impl QueueService {
 
                         match result {
                             Ok(Ok(_)) => {
-                                if let Err(e) = Self::handle_success(&manager, &queue_name, &job, &processing_key).await {
+                                let duration_ms = started.elapsed().as_millis() as u64;
+                                if let Err(e) = Self::handle_success(&ctx, job, &job_json, duration_ms).await {
                                     tracing::error!("Error handling success: {}", e);
                                 }
                             }
                             Ok(Err(e)) => {
                                 tracing::debug!("Job {} failed: {}", job.id, e);
-                                if let Err(err) = Self::handle_failure(&manager, &queue_name, job, &processing_key, &waiting_key).await {
+                                if let Err(err) = Self::handle_failure(&ctx, job, &job_json, e.to_string()).await {
                                     tracing::error!("Error handling failure: {}", err);
                                 }
                             }
                             Err(_) => {
                                 tracing::debug!("Job {} timed out", job.id);
-                                if let Err(err) = Self::handle_failure(&manager, &queue_name, job, &processing_key, &waiting_key).await {
+                                let error = format!("Job timed out after {} ms", job.timeout_ms);
+                                if let Err(err) = Self::handle_failure(&ctx, job, &job_json, error).await {
                                     tracing::error!("Error handling timeout: {}", err);
                                 }
                             }
@@ -350,25 +822,152 @@ impl QueueService {
         });
     }
 
+    /// Internal method to spawn background batch worker
+    fn spawn_batch_worker<T, F, Fut>(&self, batch: BatchOptions, handler: F)
+    where
+        T: for<'de> Deserialize<'de> + Serialize + Clone + Send + Sync + 'static,
+        F: Fn(Vec<QueueJob<T>>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<BatchOutcome, AppError>> + Send + 'static,
+    {
+        let ctx = WorkerContext::new(self);
+
+        tokio::spawn(async move {
+            tracing::info!(
+                "🚀 Batch worker started for queue: {} (max {} jobs / {} ms)",
+                ctx.queue_name,
+                batch.max_size,
+                batch.max_wait.as_millis()
+            );
+
+            let mut last_maintenance = Instant::now();
+
+            loop {
+                let Some(mut conn) = ctx.ready_connection(&mut last_maintenance).await else {
+                    continue;
+                };
+
+                // Block for the first job of the batch
+                let first: Result<Option<String>, _> =
+                    conn.brpoplpush(&ctx.waiting_key, &ctx.processing_key, 5.0).await;
+
+                let first = match first {
+                    Ok(Some(job_json)) => job_json,
+                    Ok(None) => {
+                        sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                    Err(_) => {
+                        sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                };
+
+                // Fill the batch until it is full or the wait window closes
+                let mut raw_jobs = vec![first];
+                let deadline = Instant::now() + batch.max_wait;
+                while raw_jobs.len() < batch.max_size {
+                    let next: Option<String> = conn
+                        .rpoplpush(&ctx.waiting_key, &ctx.processing_key)
+                        .await
+                        .unwrap_or(None);
+
+                    match next {
+                        Some(job_json) => raw_jobs.push(job_json),
+                        None => {
+                            let now = Instant::now();
+                            if now >= deadline {
+                                break;
+                            }
+                            sleep((deadline - now).min(Duration::from_millis(25))).await;
+                        }
+                    }
+                }
+
+                let mut jobs: Vec<(QueueJob<T>, String)> = Vec::with_capacity(raw_jobs.len());
+                for job_json in raw_jobs {
+                    match serde_json::from_str::<QueueJob<T>>(&job_json) {
+                        Ok(mut job) => {
+                            ctx.mark_started(&mut conn, &mut job).await;
+                            jobs.push((job, job_json));
+                        }
+                        Err(e) => tracing::warn!("Skipping malformed job in queue '{}': {}", ctx.queue_name, e),
+                    }
+                }
+
+                if jobs.is_empty() {
+                    continue;
+                }
+
+                tracing::debug!("Processing batch of {} jobs in queue '{}'", jobs.len(), ctx.queue_name);
+
+                // The batch gets the longest timeout of its jobs
+                let timeout_ms = jobs.iter().map(|(job, _)| job.timeout_ms).max().unwrap_or(0);
+                let batch_jobs: Vec<QueueJob<T>> = jobs.iter().map(|(job, _)| job.clone()).collect();
+                let started = Instant::now();
+
+                let result = tokio::time::timeout(Duration::from_millis(timeout_ms), handler(batch_jobs)).await;
+                let duration_ms = started.elapsed().as_millis() as u64;
+
+                let outcome: Result<BatchOutcome, String> = match result {
+                    Ok(Ok(outcome)) => Ok(outcome),
+                    Ok(Err(e)) => Err(e.to_string()),
+                    Err(_) => Err(format!("Batch timed out after {} ms", timeout_ms)),
+                };
+
+                for (job, job_json) in jobs {
+                    let error = match &outcome {
+                        Ok(outcome) => outcome.error_for(&job.id).map(str::to_string),
+                        Err(e) => Some(e.clone()),
+                    };
+
+                    let handled = match error {
+                        None => Self::handle_success(&ctx, job, &job_json, duration_ms).await,
+                        Some(error) => {
+                            tracing::debug!("Job {} failed in batch: {}", job.id, error);
+                            Self::handle_failure(&ctx, job, &job_json, error).await
+                        }
+                    };
+
+                    if let Err(e) = handled {
+                        tracing::error!("Error finishing batch job: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
     async fn handle_success<T>(
-        manager: &QueueManager,
-        queue_name: &str,
-        job: &QueueJob<T>,
-        processing_key: &str,
+        ctx: &WorkerContext,
+        mut job: QueueJob<T>,
+        raw_job_json: &str,
+        duration_ms: u64,
     ) -> Result<(), AppError>
     where
         T: Serialize + Clone,
     {
-        let job_json = serde_json::to_string(job)
+        let (manager, queue_name, options) = (&ctx.manager, ctx.queue_name.as_str(), &ctx.options);
+        job.finished_at = Some(chrono::Utc::now().timestamp());
+        let job_json = serde_json::to_string(&job)
             .map_err(|e| AppError::QueueError(format!("Failed to serialize job: {}", e)))?;
         
         let result = timeout(Duration::from_secs(3), async {
             let mut conn = manager.get_connection().await?;
 
-            // Remove from processing
-            conn.lrem::<_, _, ()>(processing_key, 1, &job_json).await?;
+            // Remove from processing (stored as it was when popped)
+            conn.lrem::<_, _, ()>(&ctx.processing_key, 1, raw_job_json).await?;
+            conn.hdel::<_, _, ()>(&ctx.active_key, &job.id).await?;
 
-            if manager.config.remove_on_success {
+            // Throughput counter for the dashboard
+            let metric_key = QueueManager::completed_metric_key(queue_name, chrono::Utc::now().timestamp() / 60);
+            redis::pipe()
+                .incr(&metric_key, 1)
+                .ignore()
+                .expire(&metric_key, METRICS_TTL_SECS)
+                .ignore()
+                .query_async::<()>(&mut conn)
+                .await?;
+
+            if options.on_success.remove {
                 // Remove job data
                 let job_key = format!("{}:job:{}", queue_name, job.id);
                 conn.del::<_, ()>(&job_key).await?;
@@ -376,66 +975,340 @@ impl QueueService {
                 // Move to succeeded list
                 let succeeded_key = format!("{}:succeeded", queue_name);
                 conn.lpush::<_, _, ()>(&succeeded_key, &job_json).await?;
+                Self::trim_finished(&mut conn, queue_name, &succeeded_key, &options.on_success).await?;
             }
 
             Ok::<(), AppError>(())
         }).await;
 
         match result {
-            Ok(res) => res,
-            Err(_) => Err(AppError::RedisError(format!("Timeout handling success for job {}", job.id))),
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(e),
+            Err(_) => return Err(AppError::RedisError(format!("Timeout handling success for job {}", job.id))),
         }
+
+        // Outside the timeout: a slow publish must not fail bookkeeping that already happened
+        manager
+            .emit(
+                queue_name,
+                &job.id,
+                job.user_id.as_deref(),
+                QueueEventKind::Completed { attempt: job.attempts, duration_ms },
+                None,
+            )
+            .await;
+
+        Ok(())
     }
 
     async fn handle_failure<T>(
-        manager: &QueueManager,
-        queue_name: &str,
-        job: QueueJob<T>,
-        processing_key: &str,
-        waiting_key: &str,
+        ctx: &WorkerContext,
+        mut job: QueueJob<T>,
+        raw_job_json: &str,
+        error: String,
     ) -> Result<(), AppError>
     where
         T: Serialize + Clone,
     {
-        let job_json = serde_json::to_string(&job)
-            .map_err(|e| AppError::QueueError(format!("Failed to serialize job: {}", e)))?;
-        
+        let (manager, queue_name) = (&ctx.manager, ctx.queue_name.as_str());
+
         let result = timeout(Duration::from_secs(3), async {
             let mut conn = manager.get_connection().await?;
 
-            // Remove from processing
-            conn.lrem::<_, _, ()>(processing_key, 1, &job_json).await?;
+            // Remove from processing (stored as it was when popped)
+            conn.lrem::<_, _, ()>(&ctx.processing_key, 1, raw_job_json).await?;
+            conn.hdel::<_, _, ()>(&ctx.active_key, &job.id).await?;
 
             if job.attempts < job.max_retries {
                 // Calculate exponential backoff
                 let backoff = job.backoff_ms * (2_u64.pow(job.attempts - 1));
                 tracing::debug!("Retrying job {} (attempt {}/{}) after {} ms", job.id, job.attempts, job.max_retries, backoff);
 
-                sleep(Duration::from_millis(backoff)).await;
-
-                // Re-queue job
+                // Park the job in the delayed set until its backoff elapses
                 let updated_job_json = serde_json::to_string(&job)?;
-                conn.lpush::<_, _, ()>(waiting_key, &updated_job_json).await?;
+                let ready_at = chrono::Utc::now().timestamp_millis() + backoff as i64;
+                conn.zadd::<_, _, _, ()>(&ctx.delayed_key, &updated_job_json, ready_at).await?;
+
+                Ok::<_, AppError>(Some(backoff))
             } else {
-                tracing::debug!("Job {} failed permanently after {} attempts", job.id, job.attempts);
+                Self::fail_permanently(ctx, &mut conn, &mut job).await?;
+                Ok(None)
+            }
+        }).await;
+
+        let retry_delay = match result {
+            Ok(res) => res?,
+            Err(_) => return Err(AppError::RedisError(format!("Timeout handling failure for job {}", job.id))),
+        };
 
-                if manager.config.remove_on_failure {
-                    // Remove job data
-                    let job_key = format!("{}:job:{}", queue_name, job.id);
-                    conn.del::<_, ()>(&job_key).await?;
+        // Outside the timeout: a slow publish must not fail bookkeeping that already happened
+        match retry_delay {
+            Some(delay_ms) => {
+                manager
+                    .emit(
+                        queue_name,
+                        &job.id,
+                        job.user_id.as_deref(),
+                        QueueEventKind::Retrying { attempt: job.attempts, delay_ms, error },
+                        None,
+                    )
+                    .await;
+            }
+            None => Self::emit_failed(ctx, &job, error).await,
+        }
+
+        Ok(())
+    }
+
+    /// Move a job with no retries left to the failed list (or drop it, per retention)
+    ///
+    /// Callers emit `failed` with `emit_failed` once their bookkeeping is done.
+    async fn fail_permanently<T>(
+        ctx: &WorkerContext,
+        conn: &mut ConnectionManager,
+        job: &mut QueueJob<T>,
+    ) -> Result<(), AppError>
+    where
+        T: Serialize + Clone,
+    {
+        let (queue_name, options) = (ctx.queue_name.as_str(), &ctx.options);
+        tracing::debug!("Job {} failed permanently after {} attempts", job.id, job.attempts);
+
+        if options.on_failure.remove {
+            // Remove job data
+            let job_key = format!("{}:job:{}", queue_name, job.id);
+            conn.del::<_, ()>(&job_key).await?;
+        } else {
+            // Move to failed list
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+            let failed_json = serde_json::to_string(&job)?;
+            let failed_key = format!("{}:failed", queue_name);
+            conn.lpush::<_, _, ()>(&failed_key, &failed_json).await?;
+            Self::trim_finished(conn, queue_name, &failed_key, &options.on_failure).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Emit `failed` for a job that `fail_permanently` has just handled
+    async fn emit_failed<T>(ctx: &WorkerContext, job: &QueueJob<T>, error: String)
+    where
+        T: Serialize + Clone,
+    {
+        ctx.manager
+            .emit(
+                &ctx.queue_name,
+                &job.id,
+                job.user_id.as_deref(),
+                QueueEventKind::Failed { attempts: job.attempts, error },
+                serde_json::to_value(&job.data).ok(),
+            )
+            .await;
+    }
+
+    /// Trim succeeded and failed lists of a queue according to its retention policies
+    async fn apply_retention(manager: &QueueManager, queue_name: &str, options: &QueueOptions) -> Result<(), AppError> {
+        let mut conn = manager.get_connection().await?;
+
+        let succeeded_key = format!("{}:succeeded", queue_name);
+        Self::trim_finished(&mut conn, queue_name, &succeeded_key, &options.on_success).await?;
+
+        let failed_key = format!("{}:failed", queue_name);
+        Self::trim_finished(&mut conn, queue_name, &failed_key, &options.on_failure).await?;
+
+        Ok(())
+    }
+
+    /// Trim a finished-jobs list (newest first) and delete the job keys of removed entries
+    async fn trim_finished(
+        conn: &mut ConnectionManager,
+        queue_name: &str,
+        list_key: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<(), AppError> {
+        let mut removed: Vec<String> = Vec::new();
+
+        if let Some(keep_last) = policy.keep_last {
+            let overflow: Vec<String> = conn.lrange(list_key, keep_last as isize, -1).await?;
+            if !overflow.is_empty() {
+                if keep_last == 0 {
+                    conn.del::<_, ()>(list_key).await?;
                 } else {
-                    // Move to failed list
-                    let failed_key = format!("{}:failed", queue_name);
-                    conn.lpush::<_, _, ()>(&failed_key, &job_json).await?;
+                    conn.ltrim::<_, ()>(list_key, 0, keep_last as isize - 1).await?;
                 }
+                removed.extend(overflow);
             }
+        }
 
-            Ok::<(), AppError>(())
+        if let Some(keep_for) = policy.keep_for {
+            let cutoff = chrono::Utc::now().timestamp() - keep_for.as_secs() as i64;
+
+            // Oldest jobs sit at the tail of the list
+            while let Some(job_json) = conn.lindex::<_, Option<String>>(list_key, -1).await? {
+                let finished_at = serde_json::from_str::<QueueJob<serde_json::Value>>(&job_json)
+                    .map(|job| job.finished_at.unwrap_or(job.created_at))
+                    .unwrap_or(i64::MIN);
+                if finished_at > cutoff {
+                    break;
+                }
+
+                let count: i64 = conn.lrem(list_key, -1, &job_json).await?;
+                if count == 0 {
+                    // Another worker trimmed it first
+                    continue;
+                }
+                removed.push(job_json);
+            }
+        }
+
+        let job_keys: Vec<String> = removed
+            .iter()
+            .filter_map(|job_json| serde_json::from_str::<QueueJob<serde_json::Value>>(job_json).ok())
+            .map(|job| format!("{}:job:{}", queue_name, job.id))
+            .collect();
+
+        if !job_keys.is_empty() {
+            conn.del::<_, ()>(&job_keys).await?;
+            tracing::debug!("Trimmed {} jobs from '{}'", job_keys.len(), list_key);
+        }
+
+        Ok(())
+    }
+
+    /// Move jobs stuck in processing past their timeout back to waiting
+    ///
+    /// The stall counts as an attempt, so a job that keeps killing its worker ends up in the
+    /// failed list once its retries are used up instead of looping forever.
+    async fn recover_stalled(ctx: &WorkerContext) -> Result<(), AppError> {
+        let (manager, queue_name) = (&ctx.manager, ctx.queue_name.as_str());
+        let (processing_key, waiting_key, active_key) = (&ctx.processing_key, &ctx.waiting_key, &ctx.active_key);
+
+        let mut conn = manager.get_connection().await?;
+        let jobs: Vec<String> = conn.lrange(processing_key, 0, -1).await?;
+        if jobs.is_empty() {
+            return Ok(());
+        }
+
+        let active: HashMap<String, i64> = conn.hgetall(active_key).await?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        for job_json in jobs {
+            let mut job: QueueJob<serde_json::Value> = match serde_json::from_str(&job_json) {
+                Ok(j) => j,
+                Err(_) => continue,
+            };
+
+            let started_at = match active.get(&job.id) {
+                Some(started_at) => *started_at,
+                None => {
+                    // Popped but never marked active (e.g. worker crashed); start the clock now
+                    conn.hset_nx::<_, _, _, ()>(active_key, &job.id, now).await?;
+                    continue;
+                }
+            };
+
+            if now - started_at <= job.timeout_ms as i64 + STALLED_GRACE_MS {
+                continue;
+            }
+
+            // Only the instance that removes the entry re-queues it
+            let removed: i64 = conn.lrem(processing_key, 1, &job_json).await?;
+            if removed == 0 {
+                continue;
+            }
+
+            conn.hdel::<_, _, ()>(active_key, &job.id).await?;
+            manager
+                .emit(queue_name, &job.id, job.user_id.as_deref(), QueueEventKind::Stalled, None)
+                .await;
+
+            // The stored copy predates the attempt that stalled
+            job.attempts += 1;
+            if job.attempts < job.max_retries {
+                conn.rpush::<_, _, ()>(waiting_key, serde_json::to_string(&job)?).await?;
+                tracing::warn!(
+                    "Job {} in queue '{}' stalled (attempt {}/{}), moved back to waiting",
+                    job.id,
+                    queue_name,
+                    job.attempts,
+                    job.max_retries
+                );
+            } else {
+                tracing::warn!("Job {} in queue '{}' stalled on its last attempt, moving to failed", job.id, queue_name);
+                Self::fail_permanently(ctx, &mut conn, &mut job).await?;
+                Self::emit_failed(ctx, &job, "Job stalled (worker died or timed out)".to_string()).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pause processing on every instance (in-flight jobs still finish)
+    pub async fn pause(&self) -> Result<(), AppError> {
+        let mut conn = self.manager.get_connection().await?;
+        conn.set::<_, _, ()>(format!("{}:paused", self.queue_name), 1).await?;
+
+        tracing::info!("Queue '{}' paused", self.queue_name);
+        Ok(())
+    }
+
+    /// Resume processing on every instance
+    pub async fn resume(&self) -> Result<(), AppError> {
+        let mut conn = self.manager.get_connection().await?;
+        conn.del::<_, ()>(format!("{}:paused", self.queue_name)).await?;
+
+        tracing::info!("Queue '{}' resumed", self.queue_name);
+        Ok(())
+    }
+
+    /// Check whether the queue is paused
+    pub async fn is_paused(&self) -> Result<bool, AppError> {
+        let mut conn = self.manager.get_connection().await?;
+        let paused: bool = conn.exists(format!("{}:paused", self.queue_name)).await?;
+        Ok(paused)
+    }
+
+    /// Remove all waiting (and delayed) jobs, returns the number of jobs removed
+    pub async fn drain(&self) -> Result<usize, AppError> {
+        let waiting_key = format!("{}:waiting", self.queue_name);
+        let delayed_key = format!("{}:delayed", self.queue_name);
+
+        let result = timeout(Duration::from_secs(10), async {
+            let mut conn = self.manager.get_connection().await?;
+
+            // Read and delete atomically so no job is lost between the two calls
+            let (mut jobs, delayed): (Vec<String>, Vec<String>) = redis::pipe()
+                .atomic()
+                .lrange(&waiting_key, 0, -1)
+                .zrange(&delayed_key, 0, -1)
+                .del(&waiting_key)
+                .ignore()
+                .del(&delayed_key)
+                .ignore()
+                .query_async(&mut conn)
+                .await?;
+            jobs.extend(delayed);
+
+            let job_keys: Vec<String> = jobs
+                .iter()
+                .filter_map(|job_json| serde_json::from_str::<QueueJob<serde_json::Value>>(job_json).ok())
+                .map(|job| format!("{}:job:{}", self.queue_name, job.id))
+                .collect();
+
+            if !job_keys.is_empty() {
+                conn.del::<_, ()>(&job_keys).await?;
+            }
+
+            Ok::<usize, AppError>(jobs.len())
         }).await;
 
         match result {
-            Ok(res) => res,
-            Err(_) => Err(AppError::RedisError(format!("Timeout handling failure for job {}", job.id))),
+            Ok(Ok(count)) => {
+                tracing::info!("Drained {} waiting jobs from queue '{}'", count, self.queue_name);
+                Ok(count)
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(AppError::RedisError(format!("Timeout draining queue '{}'", self.queue_name))),
         }
     }
 
@@ -445,7 +1318,7 @@ impl QueueService {
             return Err(AppError::RedisError("Redis is not available. Cannot get queue stats.".to_string()));
         }
         
-        self.manager.get_stats(&self.queue_name).await
+        self.manager.get_stats(&self.name).await
     }
 
     /// Get queue name
@@ -5,7 +5,13 @@ use axum::{
 };
 
 use crate::config::AppState;
-use crate::handlers::{delete_user, get_user, health_check, login, register, update_user};
+use crate::handlers::{
+    broker_acl, broker_auth, broker_superuser, event_socket, event_stream,
+    create_device, delete_device, delete_user, drain_queue, email_webhook, get_device, get_email_log, get_preferences,
+    get_queue_stats, get_telemetry, get_user, health_check, list_devices, list_queues, login, pause_queue,
+    preview_email, queue_dashboard, register, resume_queue, rotate_device_key, search_email_logs, unsubscribe,
+    unsubscribe_page, update_device, update_preferences, update_user,
+};
 use crate::middleware::JwtMiddleware;
 
 /// Create API router
@@ -17,21 +23,53 @@ pub fn create_router(state: AppState) -> Router {
     // Public API routes (no authentication required)
     let public_routes = Router::new()
         .route("/auth/register", post(register))
-        .route("/auth/login", post(login));
+        .route("/auth/login", post(login))
+        .route("/email/unsubscribe/:token", get(unsubscribe_page).post(unsubscribe))
+        .route("/webhooks/email", post(email_webhook))
+        .route("/mqtt/auth", post(broker_auth))
+        .route("/mqtt/superuser", post(broker_superuser))
+        .route("/mqtt/acl", post(broker_acl))
+        // Authenticated in the handler: browsers can't send headers on EventSource / WebSocket
+        .route("/events", get(event_stream))
+        .route("/events/ws", get(event_socket));
 
     // Protected API routes (authentication required)
     let protected_routes = Router::new()
         .route("/user", get(get_user))
         .route("/user", put(update_user))
         .route("/user", delete(delete_user))
+        .route("/user/preferences", get(get_preferences).put(update_preferences))
+        .route("/devices", get(list_devices).post(create_device))
+        .route("/devices/:id", get(get_device).put(update_device).delete(delete_device))
+        .route("/devices/:id/key", post(rotate_device_key))
+        .route("/devices/:id/telemetry", get(get_telemetry))
         .route_layer(middleware::from_fn(JwtMiddleware::auth));
 
+    // Admin API routes (admin authentication required)
+    let admin_routes = Router::new()
+        .route("/admin/queues", get(list_queues))
+        .route("/admin/queues/:name", get(get_queue_stats))
+        .route("/admin/queues/:name/pause", post(pause_queue))
+        .route("/admin/queues/:name/resume", post(resume_queue))
+        .route("/admin/queues/:name/drain", post(drain_queue))
+        .route("/admin/emails", get(search_email_logs))
+        .route("/admin/emails/preview/:kind", get(preview_email))
+        .route("/admin/emails/:id", get(get_email_log))
+        .route_layer(middleware::from_fn_with_state(state.clone(), JwtMiddleware::admin));
+
+    // Admin pages opened in a browser (Bearer header, or `?token=` exchanged for a cookie)
+    let admin_pages = Router::new()
+        .route("/admin/queues/dashboard", get(queue_dashboard))
+        .route_layer(middleware::from_fn_with_state(state.clone(), JwtMiddleware::admin_page));
+
     // Combine routes
     Router::new()
         .merge(health_routes)  // Health check at /health
         .nest("/api", Router::new()
             .merge(public_routes)
             .merge(protected_routes)
+            .merge(admin_routes)
+            .merge(admin_pages)
         )
         .with_state(state)
 }
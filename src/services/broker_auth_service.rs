@@ -0,0 +1,277 @@
+use crate::config::{AclAccess, AclRole, AppState};
+use crate::dto::{BrokerAclRequest, BrokerAuthRequest};
+use crate::interceptors::AppError;
+use crate::middleware::verify_token;
+use crate::models::User;
+use crate::services::device_service::DeviceService;
+use crate::utils::{constant_time_eq, verify_password};
+
+/// Who a broker client is authenticated as
+#[derive(Debug, Clone)]
+pub enum BrokerIdentity {
+    /// This backend, using `MQTT_USERNAME` / `MQTT_PASSWORD`
+    Service,
+    /// A registered device (username = device ID, password = device key)
+    Device { id: String },
+    /// A user (username = email or user ID, password = account password or JWT)
+    User { id: String },
+}
+
+impl BrokerIdentity {
+    pub fn is_superuser(&self) -> bool {
+        matches!(self, BrokerIdentity::Service)
+    }
+
+    fn role_matches(&self, role: AclRole) -> bool {
+        match self {
+            BrokerIdentity::Service => false,
+            BrokerIdentity::Device { .. } => matches!(role, AclRole::Device | AclRole::Any),
+            BrokerIdentity::User { .. } => matches!(role, AclRole::User | AclRole::Any),
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            BrokerIdentity::Service => "",
+            BrokerIdentity::Device { id } | BrokerIdentity::User { id } => id,
+        }
+    }
+}
+
+/// Answers the MQTT broker's authentication and ACL checks from the user and device tables
+#[derive(Clone)]
+pub struct BrokerAuthService {
+    state: AppState,
+}
+
+impl BrokerAuthService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Check a connecting client's credentials; `bearer` is a JWT sent in the Authorization header
+    pub async fn authenticate(
+        &self,
+        request: &BrokerAuthRequest,
+        bearer: Option<&str>,
+    ) -> Result<Option<BrokerIdentity>, AppError> {
+        if self.is_service_login(&request.username, &request.password) {
+            return Ok(Some(BrokerIdentity::Service));
+        }
+
+        let devices = DeviceService::new(self.state.clone());
+        if let Some(device) = devices.find(&request.username).await? {
+            return Ok(DeviceService::authenticate(Some(&device), &request.password)
+                .then_some(BrokerIdentity::Device { id: device.id }));
+        }
+
+        let Some(user) = self.find_user(&request.username).await? else {
+            return Ok(None);
+        };
+        if !user.is_active {
+            return Ok(None);
+        }
+
+        // A JWT from the Authorization header, or passed as the password
+        let token = bearer.or_else(|| (request.password.split('.').count() == 3).then_some(request.password.as_str()));
+        if let Some(claims) = token.and_then(|token| verify_token(token).ok()) {
+            if claims.id == user.id {
+                return Ok(Some(BrokerIdentity::User { id: user.id }));
+            }
+        }
+
+        Ok(verify_password(&request.password, &user.password_hash)?.then_some(BrokerIdentity::User { id: user.id }))
+    }
+
+    /// Whether `username` gets unrestricted access
+    pub fn is_superuser(&self, username: &str) -> bool {
+        self.state
+            .broker_auth
+            .service_username
+            .as_deref()
+            .is_some_and(|service| service == username)
+    }
+
+    /// Check a publish or subscribe against the configured ACL rules
+    pub async fn check_acl(&self, request: &BrokerAclRequest) -> Result<bool, AppError> {
+        let required: &[AclAccess] = match (request.acc, request.action.as_deref()) {
+            (Some(1 | 4), _) | (None, Some("subscribe")) => &[AclAccess::Subscribe],
+            (Some(2), _) | (None, Some("publish")) => &[AclAccess::Publish],
+            (Some(3), _) => &[AclAccess::Publish, AclAccess::Subscribe],
+            _ => return Err(AppError::BadRequest("Missing or unknown ACL access".to_string())),
+        };
+
+        let Some(identity) = self.identify(&request.username).await? else {
+            return Ok(false);
+        };
+        if identity.is_superuser() {
+            return Ok(true);
+        }
+
+        Ok(required.iter().all(|access| self.is_granted(&identity, request, *access)))
+    }
+
+    fn is_granted(&self, identity: &BrokerIdentity, request: &BrokerAclRequest, access: AclAccess) -> bool {
+        self.state
+            .broker_auth
+            .acl_rules
+            .iter()
+            .filter(|rule| identity.role_matches(rule.role) && rule.access.allows(access))
+            .filter_map(|rule| expand_pattern(&rule.pattern, identity.id(), &request.username, &request.clientid))
+            .any(|filter| filter_covers(&filter, &request.topic))
+    }
+
+    /// Resolve an already authenticated username (the broker only sends usernames for ACL checks)
+    async fn identify(&self, username: &str) -> Result<Option<BrokerIdentity>, AppError> {
+        if self.is_superuser(username) {
+            return Ok(Some(BrokerIdentity::Service));
+        }
+
+        if let Some(device) = DeviceService::new(self.state.clone()).find(username).await? {
+            return Ok(device.is_active.then_some(BrokerIdentity::Device { id: device.id }));
+        }
+
+        Ok(self
+            .find_user(username)
+            .await?
+            .filter(|user| user.is_active)
+            .map(|user| BrokerIdentity::User { id: user.id }))
+    }
+
+    fn is_service_login(&self, username: &str, password: &str) -> bool {
+        let config = &self.state.broker_auth;
+        match (&config.service_username, &config.service_password) {
+            (Some(service_username), Some(service_password)) => {
+                username == service_username && constant_time_eq(password.as_bytes(), service_password.as_bytes())
+            }
+            _ => false,
+        }
+    }
+
+    async fn find_user(&self, username: &str) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1 OR id = $1")
+            .bind(username)
+            .fetch_optional(&self.state.db)
+            .await?;
+
+        Ok(user)
+    }
+}
+
+/// Fill in a rule's placeholders; rules whose values would add levels or wildcards don't apply
+///
+/// Placeholders are replaced in one pass, so a value that looks like a placeholder stays literal.
+fn expand_pattern(pattern: &str, id: &str, username: &str, clientid: &str) -> Option<String> {
+    let mut filter = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        filter.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some((placeholder, value)) = [("{id}", id), ("{username}", username), ("{clientid}", clientid)]
+            .into_iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        else {
+            filter.push('{');
+            rest = &rest[1..];
+            continue;
+        };
+        // `$` would turn a leading level into a reserved (`$SYS`-style) topic
+        if value.is_empty() || value.contains(['/', '+', '#']) || value.starts_with('$') {
+            return None;
+        }
+        filter.push_str(value);
+        rest = &rest[placeholder.len()..];
+    }
+    filter.push_str(rest);
+
+    Some(filter)
+}
+
+/// Whether every topic matched by `requested` (a topic or a subscription filter) is matched by `granted`
+fn filter_covers(granted: &str, requested: &str) -> bool {
+    // Wildcards never match `$SYS`-style topics
+    if requested.starts_with('$') && (granted.starts_with('+') || granted.starts_with('#')) {
+        return false;
+    }
+
+    let mut granted_levels = granted.split('/');
+    let mut requested_levels = requested.split('/');
+
+    loop {
+        match (granted_levels.next(), requested_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(level)) if level != "#" => continue,
+            (Some(granted), Some(requested)) if granted == requested && requested != "+" && requested != "#" => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(pattern: &str, id: &str) -> Option<String> {
+        expand_pattern(pattern, id, "alice@example.com", "client-1")
+    }
+
+    #[test]
+    fn expand_pattern_fills_placeholders() {
+        assert_eq!(expand("devices/{id}/#", "42").as_deref(), Some("devices/42/#"));
+        assert_eq!(
+            expand("users/{username}/{clientid}/{id}", "7").as_deref(),
+            Some("users/alice@example.com/client-1/7")
+        );
+        assert_eq!(expand("public/+/news", "").as_deref(), Some("public/+/news"));
+        assert_eq!(expand("literal/{other}", "42").as_deref(), Some("literal/{other}"));
+    }
+
+    #[test]
+    fn expand_pattern_rejects_identities_that_widen_the_filter() {
+        for id in ["", "+", "#", "a/b", "42/#", "dev+", "$SYS"] {
+            assert_eq!(expand("{id}/#", id), None, "id '{}' should not expand", id);
+        }
+        assert_eq!(expand_pattern("users/{username}/#", "7", "#", "client-1"), None);
+        assert_eq!(expand_pattern("users/{username}/#", "7", "a+b", "client-1"), None);
+        assert_eq!(expand_pattern("clients/{clientid}", "7", "alice", "x/+"), None);
+        // A placeholder-shaped value is not expanded again
+        assert_eq!(
+            expand_pattern("devices/{id}/{username}", "{username}", "alice", "c").as_deref(),
+            Some("devices/{username}/alice")
+        );
+    }
+
+    #[test]
+    fn filter_covers_topics() {
+        assert!(filter_covers("devices/42/#", "devices/42/telemetry"));
+        assert!(filter_covers("devices/42/#", "devices/42"));
+        assert!(filter_covers("devices/+/telemetry", "devices/42/telemetry"));
+        assert!(filter_covers("#", "anything/at/all"));
+        assert!(!filter_covers("devices/42/#", "devices/43/telemetry"));
+        assert!(!filter_covers("devices/+/telemetry", "devices/42/status"));
+        assert!(!filter_covers("devices/+", "devices/42/telemetry"));
+        assert!(!filter_covers("devices/42", "devices/42/telemetry"));
+    }
+
+    #[test]
+    fn filter_covers_only_narrower_subscriptions() {
+        assert!(filter_covers("devices/42/#", "devices/42/+"));
+        assert!(filter_covers("devices/42/#", "devices/42/#"));
+        assert!(filter_covers("devices/+/telemetry", "devices/+/telemetry"));
+        assert!(!filter_covers("devices/42/#", "devices/+/telemetry"));
+        assert!(!filter_covers("devices/42/#", "devices/#"));
+        assert!(!filter_covers("devices/+/telemetry", "devices/#"));
+        assert!(!filter_covers("devices/42/telemetry", "devices/42/+"));
+        assert!(!filter_covers("users/alice/#", "#"));
+    }
+
+    #[test]
+    fn wildcards_never_cover_reserved_topics() {
+        assert!(!filter_covers("#", "$SYS/broker/uptime"));
+        assert!(!filter_covers("+/broker/uptime", "$SYS/broker/uptime"));
+        assert!(filter_covers("$SYS/#", "$SYS/broker/uptime"));
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::{Duration, Utc};
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::config::AppState;
+use crate::dto::{
+    CreateDeviceRequest, DeviceCredentialsResponse, DeviceResponse, TelemetryPoint, TelemetryQuery, TelemetryResponse,
+    UpdateDeviceRequest,
+};
+use crate::interceptors::AppError;
+use crate::models::Device;
+use crate::utils::{generate_secret, hash_secret, validate_request, verify_secret};
+
+/// Default number of points returned by a telemetry query
+const DEFAULT_TELEMETRY_LIMIT: i64 = 1000;
+/// Largest number of points returned by a telemetry query
+const MAX_TELEMETRY_LIMIT: i64 = 10_000;
+
+/// Device registry and telemetry queries
+#[derive(Clone)]
+pub struct DeviceService {
+    state: AppState,
+}
+
+impl DeviceService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Register a device for a user and issue its key
+    pub async fn create(&self, owner_id: &str, request: CreateDeviceRequest) -> Result<DeviceCredentialsResponse, AppError> {
+        validate_request(&request)?;
+
+        let key = generate_secret();
+        let device = Device::new(owner_id.to_string(), request.name, hash_secret(&key));
+
+        let device = sqlx::query_as::<_, Device>(
+            "INSERT INTO devices (id, owner_id, name, key_hash, is_active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING *",
+        )
+        .bind(&device.id)
+        .bind(&device.owner_id)
+        .bind(&device.name)
+        .bind(&device.key_hash)
+        .bind(device.is_active)
+        .bind(device.created_at)
+        .bind(device.updated_at)
+        .fetch_one(&self.state.db)
+        .await?;
+
+        Ok(DeviceCredentialsResponse {
+            device: device.to_response(),
+            key,
+        })
+    }
+
+    /// Devices owned by a user
+    pub async fn list(&self, owner_id: &str) -> Result<Vec<DeviceResponse>, AppError> {
+        let devices = sqlx::query_as::<_, Device>("SELECT * FROM devices WHERE owner_id = $1 ORDER BY created_at")
+            .bind(owner_id)
+            .fetch_all(&self.state.db)
+            .await?;
+
+        Ok(devices.iter().map(Device::to_response).collect())
+    }
+
+    /// Get a device owned by a user
+    pub async fn get(&self, owner_id: &str, device_id: &str) -> Result<DeviceResponse, AppError> {
+        Ok(self.find_owned(owner_id, device_id).await?.to_response())
+    }
+
+    /// Rename or (de)activate a device
+    pub async fn update(
+        &self,
+        owner_id: &str,
+        device_id: &str,
+        request: UpdateDeviceRequest,
+    ) -> Result<DeviceResponse, AppError> {
+        validate_request(&request)?;
+
+        let device = sqlx::query_as::<_, Device>(
+            "UPDATE devices
+             SET name = COALESCE($1, name), is_active = COALESCE($2, is_active), updated_at = NOW()
+             WHERE id = $3 AND owner_id = $4
+             RETURNING *",
+        )
+        .bind(&request.name)
+        .bind(request.is_active)
+        .bind(device_id)
+        .bind(owner_id)
+        .fetch_optional(&self.state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        Ok(device.to_response())
+    }
+
+    /// Delete a device and its telemetry
+    pub async fn delete(&self, owner_id: &str, device_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM devices WHERE id = $1 AND owner_id = $2")
+            .bind(device_id)
+            .bind(owner_id)
+            .execute(&self.state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Device not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Issue a new key, invalidating the previous one
+    pub async fn rotate_key(&self, owner_id: &str, device_id: &str) -> Result<DeviceCredentialsResponse, AppError> {
+        let key = generate_secret();
+
+        let device = sqlx::query_as::<_, Device>(
+            "UPDATE devices SET key_hash = $1, updated_at = NOW() WHERE id = $2 AND owner_id = $3 RETURNING *",
+        )
+        .bind(hash_secret(&key))
+        .bind(device_id)
+        .bind(owner_id)
+        .fetch_optional(&self.state.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        Ok(DeviceCredentialsResponse {
+            device: device.to_response(),
+            key,
+        })
+    }
+
+    /// Get a device by ID regardless of owner
+    pub async fn find(&self, device_id: &str) -> Result<Option<Device>, AppError> {
+        let device = sqlx::query_as::<_, Device>("SELECT * FROM devices WHERE id = $1")
+            .bind(device_id)
+            .fetch_optional(&self.state.db)
+            .await?;
+
+        Ok(device)
+    }
+
+    /// Check a device's key; inactive and unknown devices never authenticate
+    pub fn authenticate(device: Option<&Device>, key: &str) -> bool {
+        device.is_some_and(|device| device.is_active && verify_secret(key, &device.key_hash))
+    }
+
+    /// Telemetry of a device owned by a user, optionally downsampled into `interval`-second buckets
+    pub async fn telemetry(
+        &self,
+        owner_id: &str,
+        device_id: &str,
+        query: &TelemetryQuery,
+    ) -> Result<TelemetryResponse, AppError> {
+        self.find_owned(owner_id, device_id).await?;
+
+        let to = query.to.unwrap_or_else(Utc::now);
+        let from = query.from.unwrap_or(to - Duration::hours(24));
+        if from >= to {
+            return Err(AppError::BadRequest("'from' must be before 'to'".to_string()));
+        }
+        if query.interval.is_some_and(|interval| interval < 1) {
+            return Err(AppError::BadRequest("'interval' must be at least 1 second".to_string()));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_TELEMETRY_LIMIT).clamp(1, MAX_TELEMETRY_LIMIT);
+
+        let mut select = QueryBuilder::<Postgres>::new("SELECT metric, ");
+        match query.interval {
+            Some(interval) => {
+                select
+                    .push("to_timestamp(floor(extract(epoch FROM recorded_at)::double precision / ")
+                    .push_bind(interval)
+                    .push(") * ")
+                    .push_bind(interval)
+                    .push(") AS time, AVG(value) AS avg, MIN(value) AS min, MAX(value) AS max, COUNT(*) AS count");
+            }
+            None => {
+                select.push("recorded_at AS time, value AS avg, value AS min, value AS max, 1::BIGINT AS count");
+            }
+        }
+
+        select
+            .push(" FROM telemetry WHERE device_id = ")
+            .push_bind(device_id)
+            .push(" AND recorded_at >= ")
+            .push_bind(from)
+            .push(" AND recorded_at < ")
+            .push_bind(to);
+        if let Some(metric) = &query.metric {
+            select.push(" AND metric = ").push_bind(metric.clone());
+        }
+        if query.interval.is_some() {
+            select.push(" GROUP BY metric, time");
+        }
+        select.push(" ORDER BY time, metric LIMIT ").push_bind(limit);
+
+        let points = select.build_query_as::<TelemetryPoint>().fetch_all(&self.state.db).await?;
+
+        Ok(TelemetryResponse {
+            device_id: device_id.to_string(),
+            from,
+            to,
+            interval: query.interval,
+            points,
+        })
+    }
+
+    async fn find_owned(&self, owner_id: &str, device_id: &str) -> Result<Device, AppError> {
+        sqlx::query_as::<_, Device>("SELECT * FROM devices WHERE id = $1 AND owner_id = $2")
+            .bind(device_id)
+            .bind(owner_id)
+            .fetch_optional(&self.state.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))
+    }
+}
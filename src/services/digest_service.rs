@@ -0,0 +1,228 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use deadpool_redis::redis::{self, AsyncCommands, Script};
+use once_cell::sync::Lazy;
+
+use crate::config::AppState;
+use crate::interceptors::AppError;
+use crate::models::NotificationDelivery;
+use crate::services::email_kind::DigestItem;
+
+/// Most notifications kept per pending digest (older ones are dropped)
+const MAX_DIGEST_ITEMS: isize = 100;
+
+/// Claims older than this belong to an instance that died before queueing the digest
+const CLAIM_TIMEOUT_MS: i64 = 5 * 60 * 1000;
+
+/// Unschedule the address and move its notifications to its processing list in one step
+///
+/// The list is only deleted once the digest email is queued (`complete`), and the claim is
+/// recorded so a crash in between is recovered. Returns nil when another instance claimed
+/// the address first.
+static TAKE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"if redis.call("ZREM", KEYS[1], ARGV[1]) == 0 then
+    return false
+end
+local items = redis.call("LRANGE", KEYS[2], 0, -1)
+if #items > 0 then
+    redis.call("RPUSH", KEYS[3], unpack(items))
+    redis.call("DEL", KEYS[2])
+end
+redis.call("ZADD", KEYS[4], ARGV[2], ARGV[1])
+return redis.call("LRANGE", KEYS[3], 0, -1)"#,
+    )
+});
+
+/// Give up a claim: put the processing list back in front of newer notifications and reschedule
+/// the address (returns 0 when the claim was already completed or given up)
+static RELEASE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"if redis.call("ZREM", KEYS[4], ARGV[1]) == 0 then
+    return 0
+end
+local items = redis.call("LRANGE", KEYS[3], 0, -1)
+for i = #items, 1, -1 do
+    redis.call("LPUSH", KEYS[2], items[i])
+end
+redis.call("LTRIM", KEYS[2], -tonumber(ARGV[3]), -1)
+redis.call("DEL", KEYS[3])
+redis.call("ZADD", KEYS[1], "NX", ARGV[2], ARGV[1])
+return 1"#,
+    )
+});
+
+/// Pending notification digests stored in Redis
+///
+/// Notifications are appended to `{env}_email_digest:{email}` and the address is scheduled in the
+/// `{env}_email_digest_due` sorted set, scored by the time its digest is due. While a digest is
+/// being queued its notifications sit in `{env}_email_digest_processing:{email}` and the address
+/// in `{env}_email_digest_claims`, scored by the claim time.
+#[derive(Clone)]
+pub struct DigestService {
+    state: AppState,
+}
+
+impl DigestService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Add a notification to the recipient's pending digest
+    pub async fn add(&self, email: &str, delivery: NotificationDelivery, item: &DigestItem) -> Result<(), AppError> {
+        let email = email.trim().to_lowercase();
+        let due_at = self.next_flush(delivery, Utc::now());
+        let item_json = serde_json::to_string(item)
+            .map_err(|e| AppError::InternalError(format!("Failed to serialize digest item: {}", e)))?;
+
+        let mut conn = self.state.redis.get_connection().await?;
+        redis::pipe()
+            .atomic()
+            .rpush(self.items_key(&email), item_json)
+            .ignore()
+            .ltrim(self.items_key(&email), -MAX_DIGEST_ITEMS, -1)
+            .ignore()
+            // NX keeps the schedule set by the first notification of this window
+            .cmd("ZADD")
+            .arg(self.due_key())
+            .arg("NX")
+            .arg(due_at.timestamp_millis())
+            .arg(&email)
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Claim recipients whose digest is due and take their notifications
+    ///
+    /// Claiming removes the address from the schedule, so each digest is taken by one instance only.
+    /// Every claim must end with `complete` or `restore`; claims left behind by a crashed instance
+    /// are rescheduled here after `CLAIM_TIMEOUT_MS`.
+    pub async fn take_due(&self) -> Result<Vec<(String, Vec<DigestItem>)>, AppError> {
+        let mut conn = self.state.redis.get_connection().await?;
+        let now = Utc::now().timestamp_millis();
+
+        let abandoned: Vec<String> = conn
+            .zrangebyscore(self.claims_key(), "-inf", now - CLAIM_TIMEOUT_MS)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+        for email in abandoned {
+            tracing::warn!("Rescheduling notification digest for {} abandoned by another instance", email);
+            self.release(&mut conn, &email, now).await?;
+        }
+
+        let due: Vec<String> = conn
+            .zrangebyscore(self.due_key(), "-inf", now)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        let mut digests = Vec::new();
+        for email in due {
+            let taken: Option<Vec<String>> = TAKE_SCRIPT
+                .key(self.due_key())
+                .key(self.items_key(&email))
+                .key(self.processing_key(&email))
+                .key(self.claims_key())
+                .arg(&email)
+                .arg(now)
+                .invoke_async(&mut conn)
+                .await
+                .map_err(|e| AppError::RedisError(e.to_string()))?;
+            let Some(raw_items) = taken else {
+                continue;
+            };
+
+            let items: Vec<DigestItem> = raw_items
+                .iter()
+                .filter_map(|raw| serde_json::from_str(raw).ok())
+                .collect();
+
+            if items.is_empty() {
+                self.complete(&email).await?;
+            } else {
+                digests.push((email, items));
+            }
+        }
+
+        Ok(digests)
+    }
+
+    /// Drop a claimed digest's notifications once its email is queued (or deliberately dropped)
+    pub async fn complete(&self, email: &str) -> Result<(), AppError> {
+        let mut conn = self.state.redis.get_connection().await?;
+        redis::pipe()
+            .atomic()
+            .del(self.processing_key(email))
+            .ignore()
+            .zrem(self.claims_key(), email)
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))
+    }
+
+    /// Put a claimed digest's notifications back after a failed flush, due again at the next hourly boundary
+    pub async fn restore(&self, email: &str) -> Result<(), AppError> {
+        let due_at = self.next_flush(NotificationDelivery::Hourly, Utc::now());
+        let mut conn = self.state.redis.get_connection().await?;
+        self.release(&mut conn, email, due_at.timestamp_millis()).await
+    }
+
+    async fn release(&self, conn: &mut deadpool_redis::Connection, email: &str, due_at: i64) -> Result<(), AppError> {
+        RELEASE_SCRIPT
+            .key(self.due_key())
+            .key(self.items_key(email))
+            .key(self.processing_key(email))
+            .key(self.claims_key())
+            .arg(email)
+            .arg(due_at)
+            .arg(MAX_DIGEST_ITEMS)
+            .invoke_async::<i64>(conn)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Start of the next digest window
+    fn next_flush(&self, delivery: NotificationDelivery, now: DateTime<Utc>) -> DateTime<Utc> {
+        let this_hour = now
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(now);
+
+        match delivery {
+            NotificationDelivery::Instant => now,
+            NotificationDelivery::Hourly => this_hour + Duration::hours(1),
+            NotificationDelivery::Daily => {
+                let today = this_hour
+                    .with_hour(self.state.email_config.digest_daily_hour)
+                    .unwrap_or(this_hour);
+                if today > now {
+                    today
+                } else {
+                    today + Duration::days(1)
+                }
+            }
+        }
+    }
+
+    fn items_key(&self, email: &str) -> String {
+        format!("{}_email_digest:{}", self.state.config.environment, email)
+    }
+
+    fn due_key(&self) -> String {
+        format!("{}_email_digest_due", self.state.config.environment)
+    }
+
+    fn processing_key(&self, email: &str) -> String {
+        format!("{}_email_digest_processing:{}", self.state.config.environment, email)
+    }
+
+    fn claims_key(&self) -> String {
+        format!("{}_email_digest_claims", self.state.config.environment)
+    }
+}
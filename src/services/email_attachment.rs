@@ -0,0 +1,194 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, SinglePart};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::EmailConfig;
+use crate::interceptors::AppError;
+
+/// Where the bytes of an attachment come from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum AttachmentSource {
+    /// Small payload carried in the job itself (base64 encoded)
+    Inline { data: String },
+    /// File relative to the attachment storage root, read at send time
+    Stored { path: String },
+}
+
+/// File attached to an email
+///
+/// With a `content_id` the attachment is an inline image, referenced from HTML templates
+/// as `<img src="cid:{content_id}">`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    #[serde(flatten)]
+    pub source: AttachmentSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<String>,
+}
+
+impl EmailAttachment {
+    /// Attachment carried inline in the job payload
+    pub fn from_bytes(filename: impl Into<String>, content_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            source: AttachmentSource::Inline {
+                data: BASE64.encode(bytes),
+            },
+            content_id: None,
+        }
+    }
+
+    /// Attachment read from the storage root when the email is sent
+    pub fn from_storage(filename: impl Into<String>, content_type: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            source: AttachmentSource::Stored { path: path.into() },
+            content_id: None,
+        }
+    }
+
+    /// Turn the attachment into an inline image referenced as `cid:{content_id}`
+    pub fn with_content_id(mut self, content_id: impl Into<String>) -> Self {
+        self.content_id = Some(content_id.into());
+        self
+    }
+
+    pub fn is_inline_image(&self) -> bool {
+        self.content_id.is_some()
+    }
+}
+
+/// Resolves stored attachments and enforces size limits
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    root: PathBuf,
+    max_inline_size: u64,
+    max_attachment_size: u64,
+    max_total_size: u64,
+}
+
+impl AttachmentStore {
+    pub fn from_config(config: &EmailConfig) -> Self {
+        Self {
+            root: PathBuf::from(&config.attachment_dir),
+            max_inline_size: config.max_inline_attachment_size,
+            max_attachment_size: config.max_attachment_size,
+            max_total_size: config.max_total_attachment_size,
+        }
+    }
+
+    /// Check attachments before the job is enqueued
+    ///
+    /// Stored files must exist at this point; their size is taken from the file metadata.
+    pub async fn validate(&self, attachments: &[EmailAttachment]) -> Result<(), AppError> {
+        let mut total = 0u64;
+
+        for attachment in attachments {
+            if attachment.filename.trim().is_empty() {
+                return Err(AppError::ValidationError("Attachment filename is required".to_string()));
+            }
+
+            attachment.content_type.parse::<ContentType>().map_err(|_| {
+                AppError::ValidationError(format!(
+                    "Invalid content type '{}' for attachment '{}'",
+                    attachment.content_type, attachment.filename
+                ))
+            })?;
+
+            let size = match &attachment.source {
+                AttachmentSource::Inline { data } => {
+                    let size = Self::decode(attachment, data)
+                        .map_err(AppError::ValidationError)?
+                        .len() as u64;
+
+                    if size > self.max_inline_size {
+                        return Err(AppError::ValidationError(format!(
+                            "Inline attachment '{}' is {} bytes (max {}); store it and attach by path instead",
+                            attachment.filename, size, self.max_inline_size
+                        )));
+                    }
+                    size
+                }
+                AttachmentSource::Stored { path } => {
+                    let full_path = self.resolve(path).map_err(AppError::ValidationError)?;
+                    tokio::fs::metadata(&full_path)
+                        .await
+                        .map_err(|e| {
+                            AppError::ValidationError(format!(
+                                "Attachment '{}' not found at {}: {}",
+                                attachment.filename, path, e
+                            ))
+                        })?
+                        .len()
+                }
+            };
+
+            if size > self.max_attachment_size {
+                return Err(AppError::ValidationError(format!(
+                    "Attachment '{}' is {} bytes (max {})",
+                    attachment.filename, size, self.max_attachment_size
+                )));
+            }
+
+            total += size;
+        }
+
+        if total > self.max_total_size {
+            return Err(AppError::ValidationError(format!(
+                "Attachments total {} bytes (max {})",
+                total, self.max_total_size
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Load the attachment bytes and build its MIME part
+    pub async fn to_part(&self, attachment: &EmailAttachment) -> Result<SinglePart, AppError> {
+        let bytes = match &attachment.source {
+            AttachmentSource::Inline { data } => Self::decode(attachment, data).map_err(AppError::EmailError)?,
+            AttachmentSource::Stored { path } => {
+                let full_path = self.resolve(path).map_err(AppError::EmailError)?;
+                tokio::fs::read(&full_path)
+                    .await
+                    .map_err(|e| AppError::EmailError(format!("Failed to read attachment {}: {}", full_path.display(), e)))?
+            }
+        };
+
+        let content_type = attachment.content_type.parse::<ContentType>().map_err(|_| {
+            AppError::EmailError(format!("Invalid content type '{}'", attachment.content_type))
+        })?;
+
+        let builder = match &attachment.content_id {
+            Some(content_id) => Attachment::new_inline_with_name(content_id.clone(), attachment.filename.clone()),
+            None => Attachment::new(attachment.filename.clone()),
+        };
+
+        Ok(builder.body(bytes, content_type))
+    }
+
+    /// Resolve a stored path, rejecting anything that escapes the storage root
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(path);
+
+        if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err(format!("Attachment path '{}' must be relative to the storage root", path));
+        }
+
+        Ok(self.root.join(relative))
+    }
+
+    fn decode(attachment: &EmailAttachment, data: &str) -> Result<Vec<u8>, String> {
+        BASE64
+            .decode(data)
+            .map_err(|e| format!("Attachment '{}' is not valid base64: {}", attachment.filename, e))
+    }
+}
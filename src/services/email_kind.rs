@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::interceptors::AppError;
+use crate::services::email_templates::{EmailTemplates, RenderedEmail};
+
+/// Template data for the welcome email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WelcomeEmail {
+    pub user_id: String,
+    pub user_name: Option<String>,
+}
+
+/// Template data for the password reset email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetEmail {
+    pub email: String,
+    pub reset_token: String,
+}
+
+/// Template data for a generic notification email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationEmail {
+    pub subject: String,
+    pub message: String,
+}
+
+/// One notification collected into a digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestItem {
+    pub subject: String,
+    pub message: String,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Template data for a notification digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDigestEmail {
+    /// Oldest first
+    pub notifications: Vec<DigestItem>,
+}
+
+/// Template of each email kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    Welcome,
+    PasswordReset,
+    Notification,
+    NotificationDigest,
+}
+
+impl EmailTemplate {
+    /// All templates (validated at startup)
+    pub const ALL: [Self; 4] = [
+        EmailTemplate::Welcome,
+        EmailTemplate::PasswordReset,
+        EmailTemplate::Notification,
+        EmailTemplate::NotificationDigest,
+    ];
+
+    /// Template name, also the kind's tag in job payloads and the preview URL
+    pub fn name(self) -> &'static str {
+        match self {
+            EmailTemplate::Welcome => "welcome",
+            EmailTemplate::PasswordReset => "password_reset",
+            EmailTemplate::Notification => "notification",
+            EmailTemplate::NotificationDigest => "notification_digest",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|template| template.name() == name)
+    }
+
+    /// Names of all templates
+    pub fn names() -> [&'static str; 4] {
+        Self::ALL.map(Self::name)
+    }
+
+    /// Kind with placeholder data, for template previews
+    pub fn sample(self) -> EmailKind {
+        match self {
+            EmailTemplate::Welcome => EmailKind::Welcome(WelcomeEmail {
+                user_id: "00000000-0000-0000-0000-000000000000".to_string(),
+                user_name: Some("Jane Doe".to_string()),
+            }),
+            EmailTemplate::PasswordReset => EmailKind::PasswordReset(PasswordResetEmail {
+                email: "jane@example.com".to_string(),
+                reset_token: "sample-reset-token".to_string(),
+            }),
+            EmailTemplate::Notification => EmailKind::Notification(NotificationEmail {
+                subject: "Your export is ready".to_string(),
+                message: "The report you requested has finished processing.".to_string(),
+            }),
+            EmailTemplate::NotificationDigest => EmailKind::NotificationDigest(NotificationDigestEmail {
+                notifications: vec![
+                    DigestItem {
+                        subject: "New comment on your post".to_string(),
+                        message: "Alex replied: \"Great write-up, thanks!\"".to_string(),
+                        received_at: Utc::now() - chrono::Duration::minutes(42),
+                    },
+                    DigestItem {
+                        subject: "Your export is ready".to_string(),
+                        message: "The report you requested has finished processing.".to_string(),
+                        received_at: Utc::now() - chrono::Duration::minutes(5),
+                    },
+                ],
+            }),
+        }
+    }
+}
+
+/// Every kind of email the application sends, with its typed template data
+///
+/// Serialized as `{"kind": "welcome", "data": {...}}`. Adding a variant requires an
+/// `EmailTemplate`, templates for the default locale and handling wherever kinds are matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum EmailKind {
+    Welcome(WelcomeEmail),
+    PasswordReset(PasswordResetEmail),
+    Notification(NotificationEmail),
+    NotificationDigest(NotificationDigestEmail),
+}
+
+impl EmailKind {
+    /// Template of this kind
+    pub fn template(&self) -> EmailTemplate {
+        match self {
+            EmailKind::Welcome(_) => EmailTemplate::Welcome,
+            EmailKind::PasswordReset(_) => EmailTemplate::PasswordReset,
+            EmailKind::Notification(_) => EmailTemplate::Notification,
+            EmailKind::NotificationDigest(_) => EmailTemplate::NotificationDigest,
+        }
+    }
+
+    /// Template name of this kind
+    pub fn template_name(&self) -> &'static str {
+        self.template().name()
+    }
+
+    /// Whether recipients can opt out of this kind (transactional emails are always sent)
+    pub fn is_unsubscribable(&self) -> bool {
+        matches!(self, EmailKind::Notification(_) | EmailKind::NotificationDigest(_))
+    }
+
+    /// Render this kind with its typed data
+    pub fn render(
+        &self,
+        templates: &EmailTemplates,
+        locale: &str,
+        unsubscribe_url: Option<&str>,
+    ) -> Result<RenderedEmail, AppError> {
+        let name = self.template_name();
+
+        match self {
+            EmailKind::Welcome(data) => templates.render(name, locale, data, unsubscribe_url),
+            EmailKind::PasswordReset(data) => templates.render(name, locale, data, unsubscribe_url),
+            EmailKind::Notification(data) => templates.render(name, locale, data, unsubscribe_url),
+            EmailKind::NotificationDigest(data) => templates.render(name, locale, data, unsubscribe_url),
+        }
+    }
+}
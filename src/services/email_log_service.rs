@@ -0,0 +1,113 @@
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::config::AppState;
+use crate::dto::{EmailLogListResponse, EmailLogQuery};
+use crate::interceptors::AppError;
+use crate::models::EmailLog;
+
+/// Default page size for log searches
+const DEFAULT_LIMIT: i64 = 50;
+/// Largest page size for log searches
+const MAX_LIMIT: i64 = 500;
+
+/// Persistent record of email job outcomes
+#[derive(Clone)]
+pub struct EmailLogService {
+    state: AppState,
+}
+
+impl EmailLogService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Store a log entry
+    pub async fn record(&self, log: &EmailLog) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO email_logs (id, job_id, recipient, kind, locale, template_version, status, message_id,
+                                     provider_response, error, attempts, queued_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+        )
+        .bind(&log.id)
+        .bind(&log.job_id)
+        .bind(&log.recipient)
+        .bind(&log.kind)
+        .bind(&log.locale)
+        .bind(&log.template_version)
+        .bind(&log.status)
+        .bind(&log.message_id)
+        .bind(&log.provider_response)
+        .bind(&log.error)
+        .bind(log.attempts)
+        .bind(log.queued_at)
+        .bind(log.created_at)
+        .execute(&self.state.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a log entry by ID
+    pub async fn find(&self, id: &str) -> Result<EmailLog, AppError> {
+        sqlx::query_as::<_, EmailLog>("SELECT * FROM email_logs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.state.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Email log not found".to_string()))
+    }
+
+    /// Search log entries, newest first
+    pub async fn search(&self, query: &EmailLogQuery) -> Result<EmailLogListResponse, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM email_logs");
+        Self::push_filters(&mut count, query);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.state.db).await?;
+
+        let mut select = QueryBuilder::<Postgres>::new("SELECT * FROM email_logs");
+        Self::push_filters(&mut select, query);
+        select
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let items = select.build_query_as::<EmailLog>().fetch_all(&self.state.db).await?;
+
+        Ok(EmailLogListResponse {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    fn push_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &EmailLogQuery) {
+        let mut separator = " WHERE ";
+        let mut next = |builder: &mut QueryBuilder<'_, Postgres>| {
+            builder.push(separator);
+            separator = " AND ";
+        };
+
+        if let Some(recipient) = &query.recipient {
+            next(builder);
+            builder.push("LOWER(recipient) = ").push_bind(recipient.trim().to_lowercase());
+        }
+        if let Some(kind) = &query.kind {
+            next(builder);
+            builder.push("kind = ").push_bind(kind.clone());
+        }
+        if let Some(status) = &query.status {
+            next(builder);
+            builder.push("status = ").push_bind(status.clone());
+        }
+        if let Some(since) = query.since {
+            next(builder);
+            builder.push("created_at >= ").push_bind(since);
+        }
+        if let Some(until) = query.until {
+            next(builder);
+            builder.push("created_at < ").push_bind(until);
+        }
+    }
+}
@@ -1,19 +1,39 @@
 use serde::{Deserialize, Serialize};
-use tracing::{info, error};
+use std::time::Duration;
+use tracing::{info, error, warn};
 
 use crate::config::AppState;
 use crate::dto::UserResponse;
 use crate::interceptors::AppError;
-use crate::queue::{QueueManager, QueueJob, QueueService};
+use crate::models::{EmailLog, EmailLogStatus, NotificationDelivery};
+use crate::services::email_attachment::EmailAttachment;
+use crate::services::email_kind::{
+    DigestItem, EmailKind, NotificationDigestEmail, NotificationEmail, PasswordResetEmail, WelcomeEmail,
+};
+use crate::services::email_transport::OutgoingEmail;
+use crate::services::digest_service::DigestService;
+use crate::services::email_log_service::EmailLogService;
+use crate::services::suppression_service::SuppressionService;
+use crate::services::realtime_service::UserEvent;
+use crate::queue::{QueueEvent, QueueEventKind, QueueManager, QueueJob, QueueOptions, QueueService, RetentionPolicy};
+
+/// How often due notification digests are checked
+const DIGEST_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Email job data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailJobData {
     pub to: String,
-    pub subject: String,
-    pub body: String,
-    pub email_type: String, // "welcome", "password_reset", etc.
-    pub template_data: Option<serde_json::Value>,
+    /// Recipient locale used to pick the template variant
+    pub locale: String,
+    #[serde(flatten)]
+    pub kind: EmailKind,
+    /// Attachments and inline images (large files are referenced by path, not embedded)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<EmailAttachment>,
+    /// Recipient's unsubscribe token (only for kinds recipients can opt out of)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsubscribe_token: Option<String>,
 }
 
 /// Optimized Email Service with automatic queue processing
@@ -21,6 +41,9 @@ pub struct EmailJobData {
 pub struct EmailService {
     state: AppState,
     email_queue: QueueService,
+    suppressions: SuppressionService,
+    logs: EmailLogService,
+    digests: DigestService,
 }
 
 impl EmailService {
@@ -28,10 +51,18 @@ impl EmailService {
     pub fn new(state: AppState) -> Self {
         let manager = QueueManager::global();
         
-        // Create queue only once
-        let email_queue = manager.create_queue("email", 3);
+        // Create queue only once (keep the last 1000 failures for a week for inspection)
+        let options = QueueOptions::new(3)
+            .with_job_timeout(Duration::from_secs(30))
+            .with_failure_retention(
+                RetentionPolicy::keep_last(1000).with_keep_for(Duration::from_secs(7 * 24 * 3600)),
+            );
+        let email_queue = manager.create_queue_with_options("email", options);
 
         let service = Self {
+            suppressions: SuppressionService::new(state.clone()),
+            logs: EmailLogService::new(state.clone()),
+            digests: DigestService::new(state.clone()),
             state,
             email_queue: email_queue.clone(),
         };
@@ -50,55 +81,188 @@ impl EmailService {
         service
     }
 
+    /// Register lifecycle hooks on the email queue (call once at startup)
+    pub fn register_queue_hooks(&self) {
+        let logs = self.logs.clone();
+
+        self.email_queue.on_failed(move |event: QueueEvent| {
+            let logs = logs.clone();
+            async move {
+                let Some(data) = event.data_as::<EmailJobData>() else {
+                    return;
+                };
+                let QueueEventKind::Failed { attempts, error } = &event.kind else {
+                    return;
+                };
+
+                if matches!(data.kind, EmailKind::PasswordReset(_)) {
+                    error!(
+                        "🚨 Password reset email to {} permanently failed after {} attempts (Job ID: {}): {}",
+                        data.to, attempts, event.job_id, error
+                    );
+                }
+
+                let mut log = EmailLog::new(
+                    &event.job_id,
+                    &data.to,
+                    data.kind.template_name(),
+                    &data.locale,
+                    EmailLogStatus::Failed,
+                );
+                log.error = Some(error.clone());
+                log.attempts = *attempts as i32;
+
+                if let Err(e) = logs.record(&log).await {
+                    warn!("⚠️  Failed to record email log for job {}: {}", event.job_id, e);
+                }
+            }
+        });
+    }
+
     /// Instance method for processing email jobs (can access self and state)
     async fn process_email_job(&self, job: QueueJob<EmailJobData>) -> Result<(), AppError> {
         let data = &job.data;
-        info!("📧 Processing email job: {} - Type: {:?}", job.id, data.email_type);
-
-        // Simulate processing time based on email type
-        match data.email_type.as_str() {
-            "welcome" => {
-                info!("📬 Sending welcome email to: {}", data.to);
-                info!("   Subject: {}", data.subject);
-                // Simulate welcome email processing
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            }
-            "password_reset" => {
-                info!("🔐 Sending password reset email to: {}", data.to);
-                info!("   Subject: {}", data.subject);
-                // Simulate password reset email processing
-                tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
-            }
-            "notification" => {
-                info!("🔔 Sending notification email to: {}", data.to);
-                info!("   Subject: {}", data.subject);
-                // Simulate notification email processing
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-            }
-            _ => {
-                error!("❌ Unknown email type: {}", data.email_type);
-                return Err(AppError::ValidationError(format!("Unknown email type: {}", data.email_type)));
-            }
+        info!("📧 Processing email job: {} - Type: {}", job.id, data.kind.template_name());
+
+        match &data.kind {
+            EmailKind::Welcome(_) => info!("📬 Sending welcome email to: {}", data.to),
+            EmailKind::PasswordReset(_) => info!("🔐 Sending password reset email to: {}", data.to),
+            EmailKind::Notification(_) => info!("🔔 Sending notification email to: {}", data.to),
+            EmailKind::NotificationDigest(_) => info!("🗂️  Sending notification digest to: {}", data.to),
+        }
+
+        // The address may have bounced or unsubscribed since the job was queued
+        if let Some(reason) = self.suppressions.blocking_reason(&data.to, &data.kind).await? {
+            warn!("🚫 Skipping email job {} to suppressed address {} ({})", job.id, data.to, reason.as_str());
+
+            let mut log = self.log_entry(&job, &data.locale, EmailLogStatus::Suppressed);
+            log.error = Some(format!("Recipient suppressed ({})", reason.as_str()));
+            self.record_log(&log).await;
+            return Ok(());
         }
 
-        info!("✅ Email sent successfully to: {}", data.to);
+        let unsubscribe_url = data.unsubscribe_token.as_ref().map(|token| {
+            format!("{}/api/email/unsubscribe/{}", self.state.config.public_url, token)
+        });
+
+        let rendered = data
+            .kind
+            .render(&self.state.email_templates, &data.locale, unsubscribe_url.as_deref())?;
+
+        let email = OutgoingEmail {
+            to: data.to.clone(),
+            subject: rendered.subject,
+            text_body: rendered.text,
+            html_body: Some(rendered.html),
+            attachments: data.attachments.clone(),
+            unsubscribe_url,
+        };
+
+        let receipt = self.state.mailer.send(&email).await?;
+
+        info!("✅ Email sent successfully to: {} (Message-ID: {})", data.to, receipt.message_id);
+
+        let mut log = self.log_entry(&job, &rendered.locale, EmailLogStatus::Sent);
+        log.template_version = Some(rendered.template_version);
+        log.message_id = Some(receipt.message_id);
+        log.provider_response = receipt.provider_response;
+        self.record_log(&log).await;
+
         Ok(())
     }
 
+    fn log_entry(&self, job: &QueueJob<EmailJobData>, locale: &str, status: EmailLogStatus) -> EmailLog {
+        let mut log = EmailLog::new(&job.id, &job.data.to, job.data.kind.template_name(), locale, status);
+        log.attempts = job.attempts as i32;
+        log.queued_at = chrono::DateTime::from_timestamp(job.created_at, 0);
+        log
+    }
+
+    /// Store a log entry; a logging failure must not fail (and resend) a delivered email
+    async fn record_log(&self, log: &EmailLog) {
+        if let Err(e) = self.logs.record(log).await {
+            warn!("⚠️  Failed to record email log for job {}: {}", log.job_id, e);
+        }
+    }
+
+    /// Queue an email unless the recipient is suppressed for its kind
+    ///
+    /// With `user_id`, the job's completed/failed events are pushed to that user.
+    async fn enqueue(&self, mut email_data: EmailJobData, user_id: Option<&str>) -> Result<String, AppError> {
+        if let Some(reason) = self.suppressions.blocking_reason(&email_data.to, &email_data.kind).await? {
+            return Err(AppError::Forbidden(format!(
+                "Email address {} is suppressed ({})",
+                email_data.to,
+                reason.as_str()
+            )));
+        }
+
+        if email_data.kind.is_unsubscribable() && email_data.unsubscribe_token.is_none() {
+            email_data.unsubscribe_token = self.suppressions.unsubscribe_token_for(&email_data.to).await?;
+        }
+
+        match user_id {
+            Some(user_id) => self.email_queue.add_to_queue_for_user(user_id, email_data).await,
+            None => self.email_queue.add_to_queue(email_data).await,
+        }
+    }
+
+    /// Locale of the user registered with this email (default locale if unknown)
+    async fn locale_for(&self, email: &str) -> String {
+        let locale: Option<String> = sqlx::query_scalar("SELECT locale FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(&self.state.db)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("⚠️  Failed to look up locale for {}: {}", email, e);
+                None
+            });
+
+        locale.unwrap_or_else(|| self.state.email_templates.default_locale().to_string())
+    }
+
+    /// Queue any email kind with attachments (limits are checked before enqueueing)
+    pub async fn send_email_with_attachments(
+        &self,
+        to: &str,
+        kind: EmailKind,
+        attachments: Vec<EmailAttachment>,
+    ) -> Result<String, AppError> {
+        self.state.mailer.attachments().validate(&attachments).await?;
+
+        let email_data = EmailJobData {
+            to: to.to_string(),
+            locale: self.locale_for(to).await,
+            kind,
+            attachments,
+            unsubscribe_token: None,
+        };
+
+        let template = email_data.kind.template_name();
+        let attachment_count = email_data.attachments.len();
+        let job_id = self.enqueue(email_data, None).await?;
+        info!(
+            "📎 {} email with {} attachment(s) queued for {} (Job ID: {})",
+            template, attachment_count, to, job_id
+        );
+
+        Ok(job_id)
+    }
+
     /// Send welcome email (adds to queue)
     pub async fn send_welcome_email(&self, user: &UserResponse) -> Result<String, AppError> {
         let email_data = EmailJobData {
             to: user.email.clone(),
-            subject: "Welcome to our platform!".to_string(),
-            body: format!("Hello {}, welcome to our platform!", user.name.as_deref().unwrap_or("User")),
-            email_type: "welcome".to_string(),
-            template_data: Some(serde_json::json!({
-                "user_name": user.name,
-                "user_id": user.id
-            })),
+            locale: user.locale.clone(),
+            kind: EmailKind::Welcome(WelcomeEmail {
+                user_id: user.id.clone(),
+                user_name: user.name.clone(),
+            }),
+            attachments: Vec::new(),
+            unsubscribe_token: None,
         };
 
-        let job_id = self.email_queue.add_to_queue(email_data).await?;
+        let job_id = self.enqueue(email_data, Some(&user.id)).await?;
         info!("📧 Welcome email queued for {} (Job ID: {})", user.email, job_id);
         
         Ok(job_id)
@@ -108,36 +272,222 @@ impl EmailService {
     pub async fn send_password_reset_email(&self, email: &str, reset_token: &str) -> Result<String, AppError> {
         let email_data = EmailJobData {
             to: email.to_string(),
-            subject: "Password Reset Request".to_string(),
-            body: format!("Your password reset token is: {}", reset_token),
-            email_type: "password_reset".to_string(),
-            template_data: Some(serde_json::json!({
-                "reset_token": reset_token,
-                "email": email
-            })),
+            locale: self.locale_for(email).await,
+            kind: EmailKind::PasswordReset(PasswordResetEmail {
+                email: email.to_string(),
+                reset_token: reset_token.to_string(),
+            }),
+            attachments: Vec::new(),
+            unsubscribe_token: None,
         };
 
-        let job_id = self.email_queue.add_to_queue(email_data).await?;
+        let job_id = self.enqueue(email_data, None).await?;
         info!("🔐 Password reset email queued for {} (Job ID: {})", email, job_id);
         
         Ok(job_id)
     }
 
-    /// Send notification email (adds to queue)
-    pub async fn send_notification_email(&self, email: &str, subject: &str, message: &str) -> Result<String, AppError> {
+    /// Send notification email
+    ///
+    /// Queued right away for users on instant delivery (returns the job ID); otherwise collected
+    /// into the user's hourly or daily digest (returns `None`).
+    pub async fn send_notification_email(&self, email: &str, subject: &str, message: &str) -> Result<Option<String>, AppError> {
+        let user_id = self.user_id_for(email).await;
+        if let Some(user_id) = &user_id {
+            self.push_notification(user_id, subject, message).await;
+        }
+
+        let delivery = self.notification_delivery_for(email).await;
+
+        if delivery != NotificationDelivery::Instant {
+            let kind = EmailKind::Notification(NotificationEmail {
+                subject: subject.to_string(),
+                message: message.to_string(),
+            });
+            if let Some(reason) = self.suppressions.blocking_reason(email, &kind).await? {
+                return Err(AppError::Forbidden(format!(
+                    "Email address {} is suppressed ({})",
+                    email,
+                    reason.as_str()
+                )));
+            }
+
+            let item = DigestItem {
+                subject: subject.to_string(),
+                message: message.to_string(),
+                received_at: chrono::Utc::now(),
+            };
+            self.digests.add(email, delivery, &item).await?;
+            info!("🗂️  Notification for {} added to {} digest", email, delivery.as_str());
+
+            return Ok(None);
+        }
+
         let email_data = EmailJobData {
             to: email.to_string(),
-            subject: subject.to_string(),
-            body: message.to_string(),
-            email_type: "notification".to_string(),
-            template_data: Some(serde_json::json!({
-                "message": message
-            })),
+            locale: self.locale_for(email).await,
+            kind: EmailKind::Notification(NotificationEmail {
+                subject: subject.to_string(),
+                message: message.to_string(),
+            }),
+            attachments: Vec::new(),
+            unsubscribe_token: None,
         };
 
-        let job_id = self.email_queue.add_to_queue(email_data).await?;
+        let job_id = self.enqueue(email_data, user_id.as_deref()).await?;
         info!("🔔 Notification email queued for {} (Job ID: {})", email, job_id);
         
+        Ok(Some(job_id))
+    }
+
+    /// Send collected notifications as one digest email (adds to queue)
+    pub async fn send_notification_digest(&self, email: &str, notifications: Vec<DigestItem>) -> Result<String, AppError> {
+        let count = notifications.len();
+        let email_data = EmailJobData {
+            to: email.to_string(),
+            locale: self.locale_for(email).await,
+            kind: EmailKind::NotificationDigest(NotificationDigestEmail { notifications }),
+            attachments: Vec::new(),
+            unsubscribe_token: None,
+        };
+
+        let job_id = self.enqueue(email_data, None).await?;
+        info!("🗂️  Digest of {} notification(s) queued for {} (Job ID: {})", count, email, job_id);
+
         Ok(job_id)
     }
-}
\ No newline at end of file
+
+    /// Periodically send digests whose window has closed (call once at startup)
+    pub fn start_digest_flusher(&self) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DIGEST_FLUSH_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let due = match service.digests.take_due().await {
+                    Ok(due) => due,
+                    Err(e) => {
+                        warn!("⚠️  Failed to load due notification digests: {}", e);
+                        continue;
+                    }
+                };
+
+                for (email, items) in due {
+                    let finished = match service.send_notification_digest(&email, items).await {
+                        Ok(_) => service.digests.complete(&email).await,
+                        // Suppressed since the notifications were collected: drop them
+                        Err(AppError::Forbidden(reason)) => {
+                            info!("🚫 Dropping notification digest for {}: {}", email, reason);
+                            service.digests.complete(&email).await
+                        }
+                        Err(e) => {
+                            warn!("⚠️  Failed to queue notification digest for {}: {}", email, e);
+                            service.digests.restore(&email).await
+                        }
+                    };
+
+                    // The claim is retried once it times out, so nothing is lost (at worst sent twice)
+                    if let Err(e) = finished {
+                        error!("🚨 Failed to settle notification digest claim for {}: {}", email, e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Push a notification to the recipient's open WebSocket / SSE connections
+    async fn push_notification(&self, user_id: &str, subject: &str, message: &str) {
+        let event = UserEvent::new("notification", serde_json::json!({ "subject": subject, "message": message }));
+        if let Err(e) = self.state.realtime.send_to_user(user_id, event).await {
+            warn!("⚠️  Failed to push notification to user {}: {}", user_id, e);
+        }
+    }
+
+    /// ID of the user registered with this email, if any
+    async fn user_id_for(&self, email: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT id FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(&self.state.db)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("⚠️  Failed to look up user for {}: {}", email, e);
+                None
+            })
+    }
+
+    /// Notification delivery preference of the user registered with this email (instant if unknown)
+    async fn notification_delivery_for(&self, email: &str) -> NotificationDelivery {
+        let delivery: Option<String> = sqlx::query_scalar("SELECT notification_delivery FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(&self.state.db)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("⚠️  Failed to look up notification preference for {}: {}", email, e);
+                None
+            });
+
+        delivery
+            .as_deref()
+            .and_then(NotificationDelivery::parse)
+            .unwrap_or(NotificationDelivery::Instant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::email_kind::EmailTemplate;
+
+    #[test]
+    fn job_data_round_trips_every_kind() {
+        for template in EmailTemplate::ALL {
+            let job = EmailJobData {
+                to: "alice@example.com".to_string(),
+                locale: "en".to_string(),
+                kind: template.sample(),
+                attachments: vec![EmailAttachment::from_bytes("report.csv", "text/csv", b"a,b")],
+                unsubscribe_token: Some("token".to_string()),
+            };
+
+            let json = serde_json::to_value(&job).unwrap();
+            assert_eq!(json["kind"], template.name());
+            assert!(json["data"].is_object(), "{} data should be nested under `data`", template.name());
+            assert_eq!(json["to"], "alice@example.com");
+
+            let parsed: EmailJobData = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(parsed.kind.template(), template);
+            assert_eq!(parsed.attachments.len(), 1);
+            assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn job_data_reads_payloads_without_optional_fields() {
+        let json = serde_json::json!({
+            "to": "alice@example.com",
+            "locale": "en",
+            "kind": "notification",
+            "data": {"subject": "Hello", "message": "World"},
+        });
+
+        let parsed: EmailJobData = serde_json::from_value(json).unwrap();
+        assert!(matches!(&parsed.kind, EmailKind::Notification(email) if email.subject == "Hello"));
+        assert!(parsed.attachments.is_empty());
+        assert!(parsed.unsubscribe_token.is_none());
+    }
+
+    #[test]
+    fn job_data_rejects_unknown_kinds() {
+        let json = serde_json::json!({
+            "to": "alice@example.com",
+            "locale": "en",
+            "kind": "newsletter",
+            "data": {},
+        });
+
+        assert!(serde_json::from_value::<EmailJobData>(json).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use minijinja::{context, path_loader, Environment, Value};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::config::EmailConfig;
+use crate::interceptors::AppError;
+
+/// Template parts every email kind must provide per locale
+const TEMPLATE_PARTS: [&str; 3] = ["subject.txt", "html", "txt"];
+
+/// Shared layouts included in every template version
+const LAYOUTS: [&str; 2] = ["layout.html", "layout.txt"];
+
+/// Rendered subject and bodies for one email
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+    /// Locale the templates were actually taken from
+    pub locale: String,
+    /// Content hash of the templates used (changes whenever a part or layout is edited)
+    pub template_version: String,
+}
+
+/// Email templates loaded from `<dir>/<locale>/<kind>.{subject.txt,html,txt}`
+///
+/// HTML and text bodies extend the shared `layout.html` / `layout.txt` in the template root.
+/// Every template can use `app_name`, `locale` and `unsubscribe_url` (if any) besides its own data.
+pub struct EmailTemplates {
+    env: Environment<'static>,
+    dir: PathBuf,
+    default_locale: String,
+    locales: Vec<String>,
+    /// Template version of every "<locale>/<kind>" pair that has templates
+    versions: HashMap<String, String>,
+}
+
+impl fmt::Debug for EmailTemplates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailTemplates")
+            .field("dir", &self.dir)
+            .field("default_locale", &self.default_locale)
+            .field("locales", &self.locales)
+            .finish()
+    }
+}
+
+impl EmailTemplates {
+    /// Load templates and validate that every kind exists in the default locale
+    /// and that every template compiles
+    pub fn load(config: &EmailConfig, app_name: &str, kinds: &[&str]) -> Result<Self, AppError> {
+        let dir = PathBuf::from(&config.template_dir);
+        let default_locale = config.default_locale.as_str();
+
+        let mut env = Environment::new();
+        env.set_loader(path_loader(&dir));
+        env.add_global("app_name", app_name.to_string());
+
+        let mut locales: Vec<String> = std::fs::read_dir(&dir)
+            .map_err(|e| AppError::EmailError(format!("Failed to read template dir {}: {}", dir.display(), e)))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        locales.sort();
+
+        if !locales.iter().any(|locale| locale == default_locale) {
+            return Err(AppError::EmailError(format!(
+                "Default email locale '{}' not found in {}",
+                default_locale,
+                dir.display()
+            )));
+        }
+
+        let mut templates = Self {
+            env,
+            dir,
+            default_locale: default_locale.to_string(),
+            locales,
+            versions: HashMap::new(),
+        };
+
+        templates.versions = templates.validate(kinds)?;
+
+        tracing::info!(
+            "Email templates loaded from {} (locales: {})",
+            templates.dir.display(),
+            templates.locales.join(", ")
+        );
+
+        Ok(templates)
+    }
+
+    fn validate(&self, kinds: &[&str]) -> Result<HashMap<String, String>, AppError> {
+        let mut versions = HashMap::new();
+
+        for locale in &self.locales {
+            for kind in kinds {
+                let present: Vec<&str> = TEMPLATE_PARTS
+                    .iter()
+                    .copied()
+                    .filter(|part| self.dir.join(locale).join(format!("{}.{}", kind, part)).is_file())
+                    .collect();
+
+                // Other locales may omit a kind entirely (falls back to the default locale)
+                if present.is_empty() && locale != &self.default_locale {
+                    continue;
+                }
+
+                for part in TEMPLATE_PARTS {
+                    let name = Self::template_name(locale, kind, part);
+                    self.env.get_template(&name).map_err(|e| {
+                        AppError::EmailError(format!("Email template '{}' is missing or invalid: {}", name, e))
+                    })?;
+                }
+
+                versions.insert(format!("{}/{}", locale, kind), self.version_of(locale, kind));
+            }
+        }
+
+        Ok(versions)
+    }
+
+    /// Short SHA-256 of the template parts and shared layouts
+    fn version_of(&self, locale: &str, kind: &str) -> String {
+        let paths = TEMPLATE_PARTS
+            .iter()
+            .map(|part| self.dir.join(locale).join(format!("{}.{}", kind, part)))
+            .chain(LAYOUTS.iter().map(|layout| self.dir.join(layout)));
+
+        let mut hasher = Sha256::new();
+        for path in paths {
+            // Layouts are optional; a missing one simply doesn't contribute
+            if let Ok(bytes) = std::fs::read(&path) {
+                hasher.update(&bytes);
+            }
+        }
+
+        hasher.finalize()[..6].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Render an email kind for a locale (falls back to language, then the default locale)
+    pub fn render<T: Serialize>(
+        &self,
+        kind: &str,
+        locale: &str,
+        data: &T,
+        unsubscribe_url: Option<&str>,
+    ) -> Result<RenderedEmail, AppError> {
+        let locale = self.resolve_locale(kind, locale);
+        let ctx = context! { locale => locale, unsubscribe_url => unsubscribe_url, ..Value::from_serialize(data) };
+
+        let render = |part: &str| {
+            let name = Self::template_name(locale, kind, part);
+            self.env
+                .get_template(&name)
+                .and_then(|template| template.render(&ctx))
+                .map_err(|e| AppError::EmailError(format!("Failed to render email template '{}': {}", name, e)))
+        };
+
+        Ok(RenderedEmail {
+            subject: render("subject.txt")?.trim().to_string(),
+            html: render("html")?,
+            text: render("txt")?,
+            locale: locale.to_string(),
+            template_version: self
+                .versions
+                .get(&format!("{}/{}", locale, kind))
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Pick the most specific locale that has templates for this kind
+    fn resolve_locale<'a>(&'a self, kind: &str, requested: &'a str) -> &'a str {
+        let language = requested.split(['-', '_']).next().unwrap_or(requested);
+
+        [requested, language]
+            .into_iter()
+            .find(|candidate| self.versions.contains_key(&format!("{}/{}", candidate, kind)))
+            .unwrap_or(&self.default_locale)
+    }
+
+    fn template_name(locale: &str, kind: &str, part: &str) -> String {
+        format!("{}/{}.{}", locale, kind, part)
+    }
+}
@@ -0,0 +1,658 @@
+use async_trait::async_trait;
+use lettre::message::header::{ContentType, Header, HeaderName, HeaderValue};
+use lettre::message::dkim::{
+    DkimCanonicalization, DkimCanonicalizationType, DkimConfig, DkimSigningAlgorithm, DkimSigningKey,
+};
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::transport::smtp::PoolConfig;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::email_config::{EmailConfig, SmtpSecurity};
+use crate::interceptors::AppError;
+use crate::services::email_attachment::{AttachmentStore, EmailAttachment};
+
+/// Email ready to be delivered
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: Option<String>,
+    /// Regular attachments and inline (CID) images
+    pub attachments: Vec<EmailAttachment>,
+    /// One-click unsubscribe endpoint (adds `List-Unsubscribe` headers)
+    pub unsubscribe_url: Option<String>,
+}
+
+/// Result of a successful delivery
+#[derive(Debug, Clone)]
+pub struct DeliveryReceipt {
+    /// Message-ID header of the sent message
+    pub message_id: String,
+    /// Provider response (e.g. SMTP "queued as ..." line)
+    pub provider_response: Option<String>,
+}
+
+/// `List-Unsubscribe` header (RFC 2369)
+#[derive(Debug, Clone)]
+struct ListUnsubscribe(String);
+
+impl Header for ListUnsubscribe {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe")
+    }
+
+    fn parse(s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(s.trim().trim_start_matches('<').trim_end_matches('>').to_string()))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), format!("<{}>", self.0))
+    }
+}
+
+/// `List-Unsubscribe-Post` header marking the unsubscribe URL as one-click (RFC 8058)
+#[derive(Debug, Clone)]
+struct ListUnsubscribePost;
+
+impl Header for ListUnsubscribePost {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe-Post")
+    }
+
+    fn parse(_s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), "List-Unsubscribe=One-Click".to_string())
+    }
+}
+
+/// Delivery mechanism for built messages
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Deliver a message, returning the provider response if any
+    async fn send(&self, message: &Message) -> Result<Option<String>, AppError>;
+
+    /// Transport name for logging
+    fn name(&self) -> &'static str;
+}
+
+/// SMTP transport with connection pooling
+pub struct SmtpTransport {
+    inner: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpTransport {
+    pub fn from_config(config: &EmailConfig) -> Result<Self, AppError> {
+        let tls_parameters = || {
+            TlsParameters::new(config.smtp_host.clone())
+                .map_err(|e| AppError::EmailError(format!("Invalid TLS parameters: {}", e)))
+        };
+
+        let tls = match config.smtp_security {
+            SmtpSecurity::Starttls => Tls::Required(tls_parameters()?),
+            SmtpSecurity::Tls => Tls::Wrapper(tls_parameters()?),
+            SmtpSecurity::None => Tls::None,
+        };
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+            .port(config.smtp_port)
+            .tls(tls)
+            .timeout(Some(Duration::from_secs(config.smtp_timeout)))
+            .pool_config(PoolConfig::new().max_size(config.smtp_pool_size));
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self { inner: builder.build() })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SmtpTransport {
+    async fn send(&self, message: &Message) -> Result<Option<String>, AppError> {
+        let response = self
+            .inner
+            .send(message.clone())
+            .await
+            .map_err(|e| AppError::EmailError(format!("SMTP delivery failed: {}", e)))?;
+
+        Ok(Some(response.message().collect::<Vec<_>>().join(" ")))
+    }
+
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+}
+
+/// Development transport that only logs messages
+pub struct LogTransport;
+
+#[async_trait]
+impl EmailTransport for LogTransport {
+    async fn send(&self, message: &Message) -> Result<Option<String>, AppError> {
+        let headers = message.headers();
+        tracing::info!(
+            "📨 [log transport] To: {} | Subject: {}",
+            headers.get_raw("To").unwrap_or("-"),
+            headers.get_raw("Subject").unwrap_or("-"),
+        );
+        tracing::debug!("{}", String::from_utf8_lossy(&message.formatted()));
+
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "log"
+    }
+}
+
+/// Development transport that writes messages into a maildir (`tmp/`, `new/`, `cur/`)
+pub struct MaildirTransport {
+    root: PathBuf,
+}
+
+impl MaildirTransport {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, AppError> {
+        let root = root.into();
+
+        for dir in ["tmp", "new", "cur"] {
+            std::fs::create_dir_all(root.join(dir))
+                .map_err(|e| AppError::EmailError(format!("Failed to create maildir {}: {}", root.display(), e)))?;
+        }
+
+        Ok(Self { root })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for MaildirTransport {
+    async fn send(&self, message: &Message) -> Result<Option<String>, AppError> {
+        // Maildir delivery: write to tmp/ then atomically rename into new/
+        let file_name = format!("{}.{}.eml", chrono::Utc::now().timestamp_millis(), Uuid::new_v4());
+        let tmp_path = self.root.join("tmp").join(&file_name);
+        let new_path = self.root.join("new").join(&file_name);
+
+        tokio::fs::write(&tmp_path, message.formatted())
+            .await
+            .map_err(|e| AppError::EmailError(format!("Failed to write {}: {}", tmp_path.display(), e)))?;
+        tokio::fs::rename(&tmp_path, &new_path)
+            .await
+            .map_err(|e| AppError::EmailError(format!("Failed to move {}: {}", tmp_path.display(), e)))?;
+
+        tracing::info!("📨 [file transport] Message written to {}", new_path.display());
+        Ok(Some(new_path.display().to_string()))
+    }
+
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+/// Builds messages and hands them to the configured transport
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Arc<dyn EmailTransport>,
+    from: Mailbox,
+    attachments: AttachmentStore,
+    dkim: Option<Arc<DkimConfig>>,
+}
+
+impl fmt::Debug for Mailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mailer")
+            .field("transport", &self.transport.name())
+            .field("from", &self.from.to_string())
+            .field("dkim", &self.dkim.is_some())
+            .finish()
+    }
+}
+
+impl Mailer {
+    /// Create a mailer using the transport selected by `EMAIL_TRANSPORT`
+    pub fn from_config(config: &EmailConfig) -> Result<Self, AppError> {
+        let transport: Arc<dyn EmailTransport> = match config.transport.as_str() {
+            "smtp" => Arc::new(SmtpTransport::from_config(config)?),
+            "log" => Arc::new(LogTransport),
+            "file" => Arc::new(MaildirTransport::new(&config.file_dir)?),
+            other => {
+                return Err(AppError::EmailError(format!(
+                    "Unknown EMAIL_TRANSPORT '{}' (expected smtp, log or file)",
+                    other
+                )))
+            }
+        };
+
+        let from = config
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::EmailError(format!("Invalid EMAIL_FROM '{}': {}", config.from, e)))?;
+
+        let dkim = Self::dkim_config(config, &from)?.map(Arc::new);
+
+        tracing::info!(
+            "Email transport: {} (DKIM {})",
+            transport.name(),
+            if dkim.is_some() { "enabled" } else { "disabled" }
+        );
+
+        Ok(Self {
+            transport,
+            from,
+            attachments: AttachmentStore::from_config(config),
+            dkim,
+        })
+    }
+
+    /// DKIM signing settings, if a selector and private key are configured
+    fn dkim_config(config: &EmailConfig, from: &Mailbox) -> Result<Option<DkimConfig>, AppError> {
+        let private_key = match (&config.dkim_private_key, &config.dkim_private_key_file) {
+            (Some(key), _) => Some(key.clone()),
+            (None, Some(path)) => Some(
+                std::fs::read_to_string(path)
+                    .map_err(|e| AppError::EmailError(format!("Failed to read DKIM key {}: {}", path, e)))?,
+            ),
+            (None, None) => None,
+        };
+
+        let (selector, private_key) = match (&config.dkim_selector, private_key) {
+            (Some(selector), Some(private_key)) => (selector.clone(), private_key),
+            (None, None) => return Ok(None),
+            _ => {
+                return Err(AppError::EmailError(
+                    "DKIM_SELECTOR and DKIM_PRIVATE_KEY (or DKIM_PRIVATE_KEY_FILE) must be set together".to_string(),
+                ))
+            }
+        };
+
+        let algorithm = match config.dkim_algorithm.as_str() {
+            "rsa" => DkimSigningAlgorithm::Rsa,
+            "ed25519" => DkimSigningAlgorithm::Ed25519,
+            other => {
+                return Err(AppError::EmailError(format!(
+                    "Unknown DKIM_ALGORITHM '{}' (expected rsa or ed25519)",
+                    other
+                )))
+            }
+        };
+
+        let signing_key = DkimSigningKey::new(private_key.trim(), algorithm)
+            .map_err(|e| AppError::EmailError(format!("Invalid DKIM private key: {}", e)))?;
+
+        let domain = config
+            .dkim_domain
+            .clone()
+            .unwrap_or_else(|| from.email.domain().to_string());
+
+        // The unsubscribe headers are signed too, so a relay can't swap in its own endpoint
+        let signed_headers = [
+            "From",
+            "To",
+            "Subject",
+            "Date",
+            "Message-ID",
+            "List-Unsubscribe",
+            "List-Unsubscribe-Post",
+        ]
+            .into_iter()
+            .map(HeaderName::new_from_ascii_str)
+            .collect();
+
+        // relaxed/relaxed survives the whitespace and folding changes relays commonly make
+        let canonicalization = DkimCanonicalization {
+            header: DkimCanonicalizationType::Relaxed,
+            body: DkimCanonicalizationType::Relaxed,
+        };
+
+        tracing::info!("DKIM signing as {}._domainkey.{}", selector, domain);
+
+        Ok(Some(DkimConfig::new(selector, domain, signing_key, signed_headers, canonicalization)))
+    }
+
+    /// Attachment storage and size limits
+    pub fn attachments(&self) -> &AttachmentStore {
+        &self.attachments
+    }
+
+    /// Build and deliver an email
+    pub async fn send(&self, email: &OutgoingEmail) -> Result<DeliveryReceipt, AppError> {
+        let message_id = self.new_message_id();
+        let mut message = self.build_message(email, &message_id).await?;
+        if let Some(dkim) = &self.dkim {
+            message.sign(dkim);
+        }
+
+        let provider_response = self.transport.send(&message).await?;
+
+        Ok(DeliveryReceipt {
+            message_id,
+            provider_response,
+        })
+    }
+
+    /// Build the MIME tree:
+    /// mixed(alternative(text, related(html, inline images)), attachments), dropping empty levels
+    async fn build_message(&self, email: &OutgoingEmail, message_id: &str) -> Result<Message, AppError> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::EmailError(format!("Invalid recipient '{}': {}", email.to, e)))?;
+
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&email.subject)
+            .message_id(Some(message_id.to_string()));
+
+        if let Some(url) = &email.unsubscribe_url {
+            builder = builder
+                .header(ListUnsubscribe(url.clone()))
+                .header(ListUnsubscribePost);
+        }
+
+        let mut inline_images = Vec::new();
+        let mut attachments = Vec::new();
+        for attachment in &email.attachments {
+            let part = self.attachments.to_part(attachment).await?;
+            // Inline images only make sense next to an HTML body
+            if attachment.is_inline_image() && email.html_body.is_some() {
+                inline_images.push(part);
+            } else {
+                attachments.push(part);
+            }
+        }
+
+        let text_part = || {
+            SinglePart::builder()
+                .header(ContentType::TEXT_PLAIN)
+                .body(email.text_body.clone())
+        };
+
+        let body = email.html_body.as_ref().map(|html| {
+            let html_part = SinglePart::builder()
+                .header(ContentType::TEXT_HTML)
+                .body(html.clone());
+
+            let alternative = MultiPart::alternative().singlepart(text_part());
+            if inline_images.is_empty() {
+                alternative.singlepart(html_part)
+            } else {
+                let related = inline_images
+                    .drain(..)
+                    .fold(MultiPart::related().singlepart(html_part), |related, image| related.singlepart(image));
+                alternative.multipart(related)
+            }
+        });
+
+        let result = match (body, attachments.is_empty()) {
+            (Some(alternative), true) => builder.multipart(alternative),
+            (None, true) => builder.singlepart(text_part()),
+            (body, false) => {
+                let mixed = match body {
+                    Some(alternative) => MultiPart::mixed().multipart(alternative),
+                    None => MultiPart::mixed().singlepart(text_part()),
+                };
+                builder.multipart(attachments.into_iter().fold(mixed, |mixed, part| mixed.singlepart(part)))
+            }
+        };
+
+        result.map_err(|e| AppError::EmailError(format!("Failed to build message: {}", e)))
+    }
+
+    fn new_message_id(&self) -> String {
+        format!("<{}@{}>", Uuid::new_v4(), self.from.email.domain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use ed25519_dalek::{Signature, SigningKey, Verifier, VerifyingKey};
+    use sha2::{Digest, Sha256};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Envelope and content received by the SMTP sink
+    struct Delivered {
+        rcpt_to: Vec<String>,
+        data: String,
+    }
+
+    /// Minimal SMTP server accepting every message; returns its port and the delivered messages
+    async fn smtp_sink() -> (u16, mpsc::UnboundedReceiver<Delivered>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    let mut rcpt_to = Vec::new();
+
+                    writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                            b"250 sink\r\n"
+                        } else if command.starts_with("RCPT TO:") {
+                            rcpt_to.push(line[8..].trim().to_string());
+                            b"250 OK\r\n"
+                        } else if command == "DATA" {
+                            writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").await.unwrap();
+                            let mut data = String::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push_str(line.strip_prefix('.').unwrap_or(&line));
+                                data.push_str("\r\n");
+                            }
+                            let _ = tx.send(Delivered {
+                                rcpt_to: std::mem::take(&mut rcpt_to),
+                                data,
+                            });
+                            b"250 OK queued as test\r\n"
+                        } else if command == "QUIT" {
+                            writer.write_all(b"221 Bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        writer.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        (port, rx)
+    }
+
+    fn smtp_config(port: u16) -> EmailConfig {
+        EmailConfig {
+            transport: "smtp".to_string(),
+            from: "Rust Backend <no-reply@example.com>".to_string(),
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: port,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_security: SmtpSecurity::None,
+            smtp_pool_size: 1,
+            smtp_timeout: 5,
+            file_dir: "logs/mail".to_string(),
+            template_dir: "templates/email".to_string(),
+            default_locale: "en".to_string(),
+            attachment_dir: "storage/attachments".to_string(),
+            max_inline_attachment_size: 256 * 1024,
+            max_attachment_size: 10 * 1024 * 1024,
+            max_total_attachment_size: 20 * 1024 * 1024,
+            webhook_secret: None,
+            digest_daily_hour: 8,
+            dkim_selector: None,
+            dkim_domain: None,
+            dkim_algorithm: "rsa".to_string(),
+            dkim_private_key: None,
+            dkim_private_key_file: None,
+        }
+    }
+
+    fn welcome_email() -> OutgoingEmail {
+        OutgoingEmail {
+            to: "Alice <alice@example.com>".to_string(),
+            subject: "Welcome aboard".to_string(),
+            text_body: "Hello Alice".to_string(),
+            html_body: Some("<p>Hello Alice</p>".to_string()),
+            attachments: Vec::new(),
+            unsubscribe_url: Some("https://example.com/unsubscribe/abc".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn smtp_transport_delivers_message() {
+        let (port, mut delivered) = smtp_sink().await;
+        let mailer = Mailer::from_config(&smtp_config(port)).unwrap();
+
+        let receipt = mailer.send(&welcome_email()).await.unwrap();
+        let message = delivered.recv().await.unwrap();
+
+        assert_eq!(message.rcpt_to, vec!["<alice@example.com>"]);
+        assert!(message.data.contains("From: \"Rust Backend\" <no-reply@example.com>\r\n"));
+        assert!(message.data.contains("To: Alice <alice@example.com>\r\n"));
+        assert!(message.data.contains("Subject: Welcome aboard\r\n"));
+        assert!(message.data.contains(&format!("Message-ID: {}\r\n", receipt.message_id)));
+        assert!(message.data.contains("List-Unsubscribe: <https://example.com/unsubscribe/abc>\r\n"));
+        assert!(message.data.contains("Content-Type: multipart/alternative"));
+        assert!(message.data.contains("Hello Alice\r\n"));
+        assert!(message.data.contains("<p>Hello Alice</p>"));
+        assert_eq!(receipt.provider_response.as_deref(), Some("OK queued as test"));
+    }
+
+    /// Relaxed canonicalization of one (possibly folded) header line (RFC 6376 3.4.2)
+    fn relaxed_header(header: &str) -> String {
+        let (name, value) = header.split_once(':').unwrap();
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        format!("{}:{}", name.trim().to_lowercase(), value)
+    }
+
+    /// Relaxed canonicalization of the body (RFC 6376 3.4.4)
+    fn relaxed_body(body: &str) -> String {
+        let mut lines: Vec<String> = body
+            .split("\r\n")
+            .map(|line| {
+                let mut out = String::new();
+                for c in line.chars() {
+                    match c {
+                        ' ' | '\t' if out.ends_with(' ') => {}
+                        ' ' | '\t' => out.push(' '),
+                        c => out.push(c),
+                    }
+                }
+                out.trim_end().to_string()
+            })
+            .collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines.iter().map(|line| format!("{}\r\n", line)).collect()
+    }
+
+    /// Verify a relaxed/relaxed ed25519-sha256 DKIM signature; returns the signed header names
+    fn verify_dkim(raw: &str, key: &VerifyingKey) -> Result<Vec<String>, String> {
+        let (head, body) = raw.split_once("\r\n\r\n").ok_or("no body")?;
+        let mut headers: Vec<String> = Vec::new();
+        for line in head.split("\r\n") {
+            match headers.last_mut() {
+                Some(last) if line.starts_with([' ', '\t']) => last.push_str(line),
+                _ => headers.push(line.to_string()),
+            }
+        }
+        let header_named = |name: &str| {
+            headers
+                .iter()
+                .rev()
+                .find(|header| header.split(':').next().unwrap().trim().eq_ignore_ascii_case(name))
+        };
+
+        let signature_header = header_named("DKIM-Signature").ok_or("not signed")?;
+        let tags: Vec<(String, String)> = signature_header
+            .split_once(':')
+            .unwrap()
+            .1
+            .split(';')
+            .filter_map(|tag| tag.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.split_whitespace().collect()))
+            .collect();
+        let tag = |name: &str| tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str()).unwrap_or("");
+
+        let body_hash = base64::engine::general_purpose::STANDARD.encode(Sha256::digest(relaxed_body(body)));
+        if body_hash != tag("bh") {
+            return Err("body hash mismatch".to_string());
+        }
+
+        let signed: Vec<String> = tag("h").split(':').map(str::to_string).collect();
+        let mut data = String::new();
+        for name in &signed {
+            if let Some(header) = header_named(name) {
+                data.push_str(&relaxed_header(header));
+                data.push_str("\r\n");
+            }
+        }
+        // The signature header itself is signed with an empty `b=` and no trailing CRLF
+        let b_start = signature_header
+            .match_indices(';')
+            .map(|(i, _)| i + 1)
+            .find(|&i| signature_header[i..].trim_start().starts_with("b="))
+            .ok_or("no b= tag")?;
+        let b_value = b_start + signature_header[b_start..].find("b=").unwrap() + 2;
+        data.push_str(&relaxed_header(&signature_header[..b_value]));
+
+        let signature = base64::engine::general_purpose::STANDARD.decode(tag("b")).map_err(|e| e.to_string())?;
+        let signature = Signature::from_slice(&signature).map_err(|e| e.to_string())?;
+        key.verify(&Sha256::digest(data), &signature)
+            .map_err(|_| "signature mismatch".to_string())?;
+
+        Ok(signed)
+    }
+
+    #[tokio::test]
+    async fn dkim_signature_verifies_and_covers_unsubscribe_headers() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let (port, mut delivered) = smtp_sink().await;
+        let config = EmailConfig {
+            dkim_selector: Some("mail".to_string()),
+            dkim_algorithm: "ed25519".to_string(),
+            dkim_private_key: Some(base64::engine::general_purpose::STANDARD.encode(signing_key.to_bytes())),
+            ..smtp_config(port)
+        };
+        let mailer = Mailer::from_config(&config).unwrap();
+
+        mailer.send(&welcome_email()).await.unwrap();
+        let message = delivered.recv().await.unwrap();
+
+        let signed = verify_dkim(&message.data, &signing_key.verifying_key()).unwrap();
+        for header in ["from", "to", "subject", "date", "message-id", "list-unsubscribe", "list-unsubscribe-post"] {
+            assert!(signed.iter().any(|name| name == header), "{} is not signed", header);
+        }
+
+        // A relay rewriting the unsubscribe endpoint breaks the signature
+        let tampered = message.data.replace(
+            "List-Unsubscribe: <https://example.com/unsubscribe/abc>",
+            "List-Unsubscribe: <https://attacker.example/unsubscribe>",
+        );
+        assert!(verify_dkim(&tampered, &signing_key.verifying_key()).is_err());
+    }
+}
@@ -1,9 +1,39 @@
 pub mod redis_service;
+pub mod redis_lock;
+pub mod redis_cache;
 pub mod mqtt_service;
+pub mod mqtt_router;
+pub mod mqtt_bridge;
 pub mod user_service;
 pub mod email_service;
+pub mod email_transport;
+pub mod email_templates;
+pub mod email_kind;
+pub mod email_attachment;
+pub mod suppression_service;
+pub mod email_log_service;
+pub mod digest_service;
+pub mod device_service;
+pub mod telemetry_service;
+pub mod broker_auth_service;
+pub mod realtime_service;
 
 pub use redis_service::RedisService;
-pub use mqtt_service::MqttService;
+pub use redis_lock::RedisLock;
+pub use redis_cache::CacheOptions;
+pub use mqtt_service::{MqttService, QoS};
+pub use mqtt_router::{MqttMessage, RouteOptions, TopicFilter, TopicParams};
+pub use mqtt_bridge::{MqttBridge, MqttJob};
 pub use user_service::UserService;
 pub use email_service::EmailService;
+pub use email_transport::{EmailTransport, Mailer, OutgoingEmail};
+pub use email_templates::EmailTemplates;
+pub use email_kind::{EmailKind, EmailTemplate};
+pub use email_attachment::{AttachmentStore, EmailAttachment};
+pub use suppression_service::SuppressionService;
+pub use email_log_service::EmailLogService;
+pub use digest_service::DigestService;
+pub use device_service::DeviceService;
+pub use telemetry_service::TelemetryService;
+pub use broker_auth_service::{BrokerAuthService, BrokerIdentity};
+pub use realtime_service::{RealtimeHub, UserEvent, UserSubscription};
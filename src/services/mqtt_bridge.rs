@@ -0,0 +1,221 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+use crate::interceptors::AppError;
+use crate::queue::QueueService;
+use crate::services::mqtt_router::{MqttMessage, RouteOptions, TopicParams};
+use crate::services::mqtt_service::MqttService;
+
+/// First wait before retrying a failed enqueue (doubled after each failure)
+const ENQUEUE_RETRY_MIN_DELAY: Duration = Duration::from_millis(500);
+/// Longest wait between enqueue retries
+const ENQUEUE_RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+/// How long a message is retried before it is left unacknowledged for the broker to redeliver
+const ENQUEUE_RETRY_LIMIT: Duration = Duration::from_secs(120);
+
+/// Queue job carrying a bridged MQTT message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttJob {
+    pub topic: String,
+    /// Payload, base64 encoded so binary messages survive the JSON job format
+    pub payload: String,
+    /// Parameters captured from named levels of the bridge pattern
+    pub params: TopicParams,
+    pub retain: bool,
+    pub received_at: DateTime<Utc>,
+}
+
+impl MqttJob {
+    fn from_message(message: MqttMessage) -> Self {
+        Self {
+            topic: message.topic,
+            payload: BASE64.encode(&message.payload),
+            params: message.params,
+            retain: message.retain,
+            received_at: message.received_at,
+        }
+    }
+
+    /// Raw payload bytes
+    pub fn payload(&self) -> Result<Vec<u8>, AppError> {
+        BASE64
+            .decode(&self.payload)
+            .map_err(|e| AppError::QueueError(format!("Invalid payload in MQTT job for '{}': {}", self.topic, e)))
+    }
+
+    /// Payload deserialized from JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, AppError> {
+        serde_json::from_slice(&self.payload()?)
+            .map_err(|e| AppError::ValidationError(format!("Invalid JSON payload on '{}': {}", self.topic, e)))
+    }
+}
+
+/// Declarative mapping of MQTT topic patterns to queues
+///
+/// Matching messages are only enqueued on receipt; processing happens in the queue's workers
+/// with its retries, timeouts and failed-job retention, so slow or failing handlers never
+/// hold up the MQTT event loop. Patterns are shared subscriptions, so each message becomes one
+/// job across all instances. Messages are acknowledged only once their job is enqueued; failed
+/// enqueues are retried with backoff for up to `ENQUEUE_RETRY_LIMIT`. A message left
+/// unacknowledged (the enqueue kept failing, or the instance died) stays in the broker's session
+/// for this client and is redelivered when it reconnects within `MQTT_SESSION_EXPIRY`; with
+/// `MQTT_SESSION_EXPIRY=0` it is lost.
+#[derive(Clone, Default)]
+pub struct MqttBridge {
+    routes: Vec<(String, QueueService)>,
+}
+
+impl MqttBridge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enqueue messages matching `pattern` (same syntax as `MqttService::route`) onto `queue`
+    pub fn route(mut self, pattern: &str, queue: QueueService) -> Self {
+        self.routes.push((pattern.to_string(), queue));
+        self
+    }
+
+    /// Subscribe every pattern on the MQTT connection
+    pub async fn start(self, mqtt: &MqttService) -> Result<(), AppError> {
+        for (pattern, queue) in self.routes {
+            let queue_name = queue.get_name().to_string();
+
+            mqtt.route_raw_with(&pattern, RouteOptions::shared().ack_on_success(), move |message: MqttMessage| {
+                let queue = queue.clone();
+                async move {
+                    let topic = message.topic.clone();
+                    let job = MqttJob::from_message(message);
+                    let job_id = enqueue_with_retry(&topic, queue.get_name(), || queue.add_to_queue(job.clone())).await?;
+
+                    tracing::debug!("MQTT message from '{}' queued as job {}", topic, job_id);
+                    Ok(())
+                }
+            })
+            .await?;
+
+            tracing::info!("🌉 Bridging MQTT '{}' to queue '{}'", pattern, queue_name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Call `enqueue` until it succeeds, backing off between attempts, for up to `ENQUEUE_RETRY_LIMIT`
+///
+/// An unacknowledged message holds a slot of the broker's in-flight window, so the retries are
+/// bounded: once the limit passes the error is returned and the message waits for redelivery.
+async fn enqueue_with_retry<F, Fut>(topic: &str, queue_name: &str, mut enqueue: F) -> Result<String, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, AppError>>,
+{
+    let deadline = Instant::now() + ENQUEUE_RETRY_LIMIT;
+    let mut delay = ENQUEUE_RETRY_MIN_DELAY;
+
+    loop {
+        match enqueue().await {
+            Ok(job_id) => return Ok(job_id),
+            Err(e) if Instant::now() + delay > deadline => {
+                tracing::error!(
+                    "Giving up on MQTT message from '{}' after retrying '{}' for {:?}, leaving it unacknowledged: {}",
+                    topic,
+                    queue_name,
+                    ENQUEUE_RETRY_LIMIT,
+                    e
+                );
+                return Err(e);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to enqueue MQTT message from '{}' on '{}', retrying in {:?}: {}",
+                    topic,
+                    queue_name,
+                    delay,
+                    e
+                );
+                sleep(delay).await;
+                delay = (delay * 2).min(ENQUEUE_RETRY_MAX_DELAY);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use std::cell::Cell;
+
+    fn message(payload: &'static [u8]) -> MqttMessage {
+        MqttMessage {
+            topic: "devices/42/telemetry".to_string(),
+            payload: Bytes::from_static(payload),
+            params: TopicParams::default(),
+            retain: false,
+            response_topic: None,
+            correlation_data: None,
+            received_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn job_round_trips_binary_payload() {
+        let job = MqttJob::from_message(message(&[0xff, 0x00, 0xfe]));
+        let job: MqttJob = serde_json::from_str(&serde_json::to_string(&job).unwrap()).unwrap();
+
+        assert_eq!(job.topic, "devices/42/telemetry");
+        assert_eq!(job.payload().unwrap(), vec![0xff, 0x00, 0xfe]);
+        assert!(matches!(job.json::<serde_json::Value>(), Err(AppError::ValidationError(_))));
+    }
+
+    #[test]
+    fn job_parses_json_payload() {
+        let job = MqttJob::from_message(message(br#"{"temperature":21.5}"#));
+        let value: serde_json::Value = job.json().unwrap();
+
+        assert_eq!(value["temperature"], 21.5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enqueue_retries_until_it_succeeds() {
+        let attempts = Cell::new(0);
+        let result = enqueue_with_retry("t", "q", || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err(AppError::RedisError("down".to_string()))
+                } else {
+                    Ok("job-1".to_string())
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "job-1");
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enqueue_gives_up_after_the_retry_limit() {
+        let started = Instant::now();
+        let attempts = Cell::new(0);
+        let result = enqueue_with_retry("t", "q", || {
+            attempts.set(attempts.get() + 1);
+            async { Err(AppError::RedisError("down".to_string())) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(AppError::RedisError(_))));
+        assert!(started.elapsed() <= ENQUEUE_RETRY_LIMIT);
+        // 0.5s, 1s, 2s, ... 30s, 30s: bounded well below one attempt per second
+        assert!(attempts.get() < 12, "{} attempts", attempts.get());
+    }
+}
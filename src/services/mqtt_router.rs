@@ -0,0 +1,376 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use rumqttc::v5::mqttbytes::v5::Publish;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use crate::interceptors::AppError;
+
+/// Segment of a route pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    /// `{name}`: one level, captured as a parameter
+    Param(String),
+    /// `+`: one level
+    SingleLevel,
+    /// `#`: any number of remaining levels (last segment only)
+    MultiLevel,
+}
+
+/// Route pattern such as `devices/{id}/telemetry` or `sensors/#`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicFilter {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+impl TopicFilter {
+    /// Parse a pattern; `{name}` segments behave like `+` and capture the level
+    pub fn parse(pattern: &str) -> Result<Self, AppError> {
+        let invalid = |reason: &str| AppError::MqttError(format!("Invalid topic pattern '{}': {}", pattern, reason));
+
+        if pattern.is_empty() {
+            return Err(invalid("pattern is empty"));
+        }
+        // The broker delivers shared messages under their real topic, which such a pattern never matches
+        if pattern.starts_with("$share/") {
+            return Err(invalid("subscribe with RouteOptions::shared() instead of a '$share/' pattern"));
+        }
+
+        let levels: Vec<&str> = pattern.split('/').collect();
+        let mut segments = Vec::with_capacity(levels.len());
+
+        for (index, level) in levels.iter().enumerate() {
+            let segment = match *level {
+                "#" if index == levels.len() - 1 => Segment::MultiLevel,
+                "#" => return Err(invalid("'#' must be the last level")),
+                "+" => Segment::SingleLevel,
+                level if level.starts_with('{') && level.ends_with('}') && level.len() > 2 => {
+                    Segment::Param(level[1..level.len() - 1].to_string())
+                }
+                level if level.contains(['+', '#', '{', '}']) => {
+                    return Err(invalid("wildcards and parameters must occupy a whole level"))
+                }
+                level => Segment::Literal(level.to_string()),
+            };
+            segments.push(segment);
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    /// Pattern as registered
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// MQTT subscription filter (parameters become `+`)
+    pub fn subscription(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(level) => level.as_str(),
+                Segment::Param(_) | Segment::SingleLevel => "+",
+                Segment::MultiLevel => "#",
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Shared subscription filter (`$share/<group>/<filter>`)
+    pub fn shared_subscription(&self, group: &str) -> String {
+        format!("$share/{}/{}", group, self.subscription())
+    }
+
+    /// Match a concrete topic, returning the captured parameters
+    pub fn matches(&self, topic: &str) -> Option<TopicParams> {
+        let levels: Vec<&str> = topic.split('/').collect();
+        let mut params = HashMap::new();
+
+        // Wildcards never match `$`-prefixed system topics at the first level (MQTT spec §4.7.2)
+        if topic.starts_with('$') && !matches!(self.segments.first(), Some(Segment::Literal(_))) {
+            return None;
+        }
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            match segment {
+                // `#` also matches the parent level itself (`sensors/#` matches `sensors`)
+                Segment::MultiLevel => return Some(TopicParams(params)),
+                _ if index >= levels.len() => return None,
+                Segment::Literal(expected) if expected != levels[index] => return None,
+                Segment::Literal(_) | Segment::SingleLevel => {}
+                Segment::Param(name) => {
+                    params.insert(name.clone(), levels[index].to_string());
+                }
+            }
+        }
+
+        (levels.len() == self.segments.len()).then_some(TopicParams(params))
+    }
+}
+
+/// How a route is subscribed and acknowledged
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouteOptions {
+    /// Subscribe as `$share/<MQTT_SHARED_GROUP>/<filter>`, so each message reaches one instance
+    /// of the deployment instead of all of them
+    pub shared: bool,
+    /// Acknowledge QoS 1/2 messages only when the handler succeeds; otherwise they are
+    /// acknowledged once the handler has finished, whatever its result
+    pub ack_on_success: bool,
+}
+
+impl RouteOptions {
+    /// Options for a shared subscription
+    pub fn shared() -> Self {
+        Self {
+            shared: true,
+            ..Self::default()
+        }
+    }
+
+    /// Leave the message unacknowledged when the handler fails, so the broker redelivers it
+    /// after the next reconnect (only while it keeps our session, see `MQTT_SESSION_EXPIRY`)
+    pub fn ack_on_success(mut self) -> Self {
+        self.ack_on_success = true;
+        self
+    }
+}
+
+/// Parameters captured from `{name}` segments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TopicParams(HashMap<String, String>);
+
+impl TopicParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Parameter that the route pattern guarantees to exist
+    pub fn require(&self, name: &str) -> Result<&str, AppError> {
+        self.get(name)
+            .ok_or_else(|| AppError::MqttError(format!("Missing topic parameter '{}'", name)))
+    }
+}
+
+/// Message delivered to a route handler
+#[derive(Debug, Clone)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: Bytes,
+    pub params: TopicParams,
+    pub retain: bool,
+    /// MQTT 5 response topic set by a requester
+    pub response_topic: Option<String>,
+    /// MQTT 5 correlation data to echo in the reply
+    pub correlation_data: Option<Bytes>,
+    pub received_at: DateTime<Utc>,
+}
+
+impl MqttMessage {
+    /// Message for an incoming publish (parameters are filled in per route)
+    pub(crate) fn from_publish(topic: String, publish: Publish) -> Self {
+        let (response_topic, correlation_data) = publish
+            .properties
+            .map(|properties| (properties.response_topic, properties.correlation_data))
+            .unwrap_or_default();
+
+        Self {
+            topic,
+            payload: publish.payload,
+            params: TopicParams::default(),
+            retain: publish.retain,
+            response_topic,
+            correlation_data,
+            received_at: Utc::now(),
+        }
+    }
+}
+
+type BoxedHandler = Arc<dyn Fn(MqttMessage) -> BoxFuture<'static, Result<(), AppError>> + Send + Sync>;
+
+struct Route {
+    filter: TopicFilter,
+    handler: BoxedHandler,
+    ack_on_success: bool,
+}
+
+/// Topic router dispatching incoming publishes to async handlers
+#[derive(Default)]
+pub struct MqttRouter {
+    routes: Vec<Route>,
+}
+
+impl fmt::Debug for MqttRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.routes.iter().map(|route| route.filter.pattern()))
+            .finish()
+    }
+}
+
+impl MqttRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler receiving the raw message
+    pub fn route_raw<F, Fut>(&mut self, pattern: &str, options: RouteOptions, handler: F) -> Result<TopicFilter, AppError>
+    where
+        F: Fn(MqttMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let filter = TopicFilter::parse(pattern)?;
+        let handler: BoxedHandler = Arc::new(move |message| Box::pin(handler(message)));
+
+        self.routes.push(Route {
+            filter: filter.clone(),
+            handler,
+            ack_on_success: options.ack_on_success,
+        });
+
+        Ok(filter)
+    }
+
+    /// Register a handler receiving the JSON payload deserialized into `T`
+    ///
+    /// Payloads that fail to deserialize are reported as handler errors and never reach the handler.
+    pub fn route<T, F, Fut>(&mut self, pattern: &str, options: RouteOptions, handler: F) -> Result<TopicFilter, AppError>
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T, TopicParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+
+        self.route_raw(pattern, options, move |message: MqttMessage| {
+            let handler = handler.clone();
+            async move {
+                let payload = serde_json::from_slice::<T>(&message.payload).map_err(|e| {
+                    AppError::MqttError(format!(
+                        "Invalid payload on '{}' for {}: {}",
+                        message.topic,
+                        std::any::type_name::<T>(),
+                        e
+                    ))
+                })?;
+
+                handler(payload, message.params).await
+            }
+        })
+    }
+
+    /// Subscription filters of all routes (deduplicated)
+    pub fn subscriptions(&self) -> Vec<String> {
+        let mut filters: Vec<String> = self.routes.iter().map(|route| route.filter.subscription()).collect();
+        filters.sort();
+        filters.dedup();
+        filters
+    }
+
+    /// Run every matching handler on its own task so slow handlers never stall the event loop
+    ///
+    /// Each task resolves to whether its route allows acknowledging the message.
+    pub fn dispatch(&self, message: &MqttMessage) -> Vec<JoinHandle<bool>> {
+        let mut handlers = Vec::new();
+
+        for route in &self.routes {
+            let Some(params) = route.filter.matches(&message.topic) else {
+                continue;
+            };
+
+            let message = MqttMessage {
+                params,
+                ..message.clone()
+            };
+            let handler = route.handler.clone();
+            let pattern = route.filter.pattern().to_string();
+            let ack_on_success = route.ack_on_success;
+
+            handlers.push(tokio::spawn(async move {
+                match handler(message).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::error!("MQTT handler for '{}' failed: {}", pattern, e);
+                        !ack_on_success
+                    }
+                }
+            }));
+        }
+
+        handlers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(pattern: &str) -> TopicFilter {
+        TopicFilter::parse(pattern).unwrap()
+    }
+
+    #[test]
+    fn parse_rejects_invalid_patterns() {
+        for pattern in ["", "sensors/#/temp", "sensors/temp+", "devices/{id", "devices/a{id}", "$share/group/sensors/#"] {
+            assert!(TopicFilter::parse(pattern).is_err(), "'{}' should be rejected", pattern);
+        }
+    }
+
+    #[test]
+    fn subscription_replaces_params_with_wildcards() {
+        assert_eq!(filter("devices/{id}/telemetry").subscription(), "devices/+/telemetry");
+        assert_eq!(filter("sensors/+/#").subscription(), "sensors/+/#");
+        assert_eq!(filter("devices/{id}/#").shared_subscription("backend"), "$share/backend/devices/+/#");
+    }
+
+    #[test]
+    fn single_level_wildcards_match_one_level() {
+        let plus = filter("sensors/+/temp");
+        assert!(plus.matches("sensors/kitchen/temp").is_some());
+        assert!(plus.matches("sensors/temp").is_none());
+        assert!(plus.matches("sensors/a/b/temp").is_none());
+        // An empty level is still a level
+        assert!(plus.matches("sensors//temp").is_some());
+    }
+
+    #[test]
+    fn multi_level_wildcard_matches_parent_and_descendants() {
+        let hash = filter("sensors/#");
+        assert!(hash.matches("sensors").is_some());
+        assert!(hash.matches("sensors/kitchen").is_some());
+        assert!(hash.matches("sensors/kitchen/temp").is_some());
+        assert!(hash.matches("other/kitchen").is_none());
+        assert!(filter("#").matches("anything/at/all").is_some());
+    }
+
+    #[test]
+    fn params_capture_their_level() {
+        let params = filter("devices/{id}/{metric}").matches("devices/42/temp").unwrap();
+        assert_eq!(params.get("id"), Some("42"));
+        assert_eq!(params.get("metric"), Some("temp"));
+        assert!(params.require("other").is_err());
+
+        assert!(filter("devices/{id}").matches("devices/42/temp").is_none());
+        assert!(filter("devices/{id}/temp").matches("devices/42/humidity").is_none());
+    }
+
+    #[test]
+    fn wildcards_skip_system_topics() {
+        assert!(filter("#").matches("$SYS/broker/uptime").is_none());
+        assert!(filter("+/broker/uptime").matches("$SYS/broker/uptime").is_none());
+        assert!(filter("{root}/broker/uptime").matches("$SYS/broker/uptime").is_none());
+        assert!(filter("$SYS/#").matches("$SYS/broker/uptime").is_some());
+    }
+}
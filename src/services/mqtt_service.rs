@@ -1,15 +1,73 @@
-use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::Bytes;
+use rumqttc::v5::mqttbytes::v5::{ConnectProperties, Filter, LastWill, Packet, Publish, PublishProperties};
+pub use rumqttc::v5::mqttbytes::QoS;
+use rumqttc::v5::{AsyncClient, Event, EventLoop, MqttOptions};
+use rumqttc::{Outgoing, TlsConfiguration, Transport};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
 
 use crate::config::MqttConfig;
 use crate::interceptors::AppError;
+use crate::services::mqtt_router::{MqttMessage, MqttRouter, RouteOptions, TopicFilter, TopicParams};
 
-#[derive(Clone)]
+/// Delay before the first reconnect attempt (doubled after each failure)
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+
+/// How long `disconnect` waits for the event loop to send the DISCONNECT packet and stop
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// User property carrying a responder's error message instead of a reply payload
+const RPC_ERROR_PROPERTY: &str = "error";
+
+/// Publish held back while the broker is unreachable
+#[derive(Debug)]
+struct BufferedPublish {
+    topic: String,
+    qos: QoS,
+    retain: bool,
+    payload: Bytes,
+}
+
+/// Client-side session state restored on every reconnect
+#[derive(Debug, Default)]
+struct Session {
+    /// Topic filters subscribed through this service
+    subscriptions: RwLock<HashMap<String, QoS>>,
+    /// Publishes waiting for the broker, oldest first
+    offline: Mutex<VecDeque<BufferedPublish>>,
+    /// Retained presence message (topic, payload) published after every connect
+    presence: Option<(String, String)>,
+    /// Topic receiving replies to this instance's requests
+    response_topic: String,
+    /// Requests awaiting a reply, by correlation data
+    pending: Mutex<HashMap<Bytes, oneshot::Sender<Publish>>>,
+}
+
+#[derive(Debug, Clone)]
 pub struct MqttService {
     client: AsyncClient,
     config: MqttConfig,
+    /// Broker connection state, updated by the event loop
+    connected: watch::Receiver<bool>,
+    /// Set once `disconnect` is called so the event loop stops reconnecting
+    stopping: Arc<AtomicBool>,
+    /// Event loop task, awaited by `disconnect` so queued packets reach the broker
+    event_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Handlers for incoming messages, shared with the event loop
+    router: Arc<RwLock<MqttRouter>>,
+    /// Subscriptions and buffered publishes, shared with the event loop
+    session: Arc<Session>,
+    /// QoS for calls that don't pass one
+    default_qos: QoS,
 }
 
 impl MqttService {
@@ -18,70 +76,428 @@ impl MqttService {
         let config = MqttConfig::from_env()
             .map_err(|e| AppError::MqttError(format!("Failed to load MQTT config: {}", e)))?;
 
-        // Parse broker URL
-        let broker_url = config.broker.clone();
-        let (host, port) = Self::parse_broker_url(&broker_url)?;
+        Self::from_config(config).await
+    }
 
-        // Create MQTT options
-        let mut mqtt_options = MqttOptions::new(&config.client_id, host, port);
+    /// Create a client for the configured broker and wait briefly for the first connection
+    ///
+    /// An unreachable broker is not an error: the event loop keeps reconnecting in the background
+    /// and the service reports itself as disconnected until it succeeds.
+    pub async fn from_config(config: MqttConfig) -> Result<Self, AppError> {
+        let mut mqtt_options = Self::broker_options(&config).await?;
         mqtt_options.set_keep_alive(Duration::from_secs(config.keep_alive));
+        // Messages are acknowledged once their handlers are done (see `RouteOptions::ack_on_success`)
+        mqtt_options.set_manual_acks(true);
+
+        // A persistent session makes the broker keep unacknowledged messages across reconnects
+        // and redeliver them, instead of dropping them with the connection
+        if config.session_expiry > 0 {
+            let mut properties = ConnectProperties::new();
+            properties.session_expiry_interval = Some(config.session_expiry);
+            mqtt_options.set_clean_start(false);
+            mqtt_options.set_connect_properties(properties);
+        }
 
         // Set credentials if provided
         if let (Some(username), Some(password)) = (&config.username, &config.password) {
             mqtt_options.set_credentials(username, password);
         }
 
+        let default_qos = rumqttc::v5::mqttbytes::qos(config.default_qos)
+            .ok_or_else(|| AppError::MqttError(format!("Invalid MQTT_QOS {} (expected 0, 1 or 2)", config.default_qos)))?;
+
+        // The broker announces us as offline if the connection drops without a clean disconnect
+        if let Some(status_topic) = &config.status_topic {
+            mqtt_options.set_last_will(LastWill::new(
+                status_topic,
+                config.offline_payload.as_bytes(),
+                QoS::AtLeastOnce,
+                true,
+                None,
+            ));
+        }
+
         // Create client and event loop
-        let (client, mut event_loop) = AsyncClient::new(mqtt_options, 10);
+        let (client, event_loop) = AsyncClient::new(mqtt_options, 10);
+        let (connected_tx, mut connected) = watch::channel(false);
+        let stopping = Arc::new(AtomicBool::new(false));
+        let router = Arc::new(RwLock::new(MqttRouter::new()));
+        let response_topic = config
+            .response_topic
+            .clone()
+            .unwrap_or_else(|| format!("{}/responses/{}", config.client_id, Uuid::new_v4().simple()));
+        let session = Arc::new(Session {
+            subscriptions: RwLock::new(HashMap::from([(response_topic.clone(), QoS::AtLeastOnce)])),
+            presence: config
+                .status_topic
+                .clone()
+                .map(|topic| (topic, config.online_payload.clone())),
+            response_topic,
+            ..Session::default()
+        });
 
-        // Spawn event loop handler
-        tokio::spawn(async move {
-            loop {
-                match event_loop.poll().await {
-                    Ok(notification) => {
-                        if let Event::Incoming(Packet::ConnAck(_)) = notification {
-                            tracing::info!("MQTT connected successfully");
-                        }
+        let event_loop = tokio::spawn(Self::run_event_loop(
+            event_loop,
+            client.clone(),
+            router.clone(),
+            session.clone(),
+            connected_tx,
+            stopping.clone(),
+            Duration::from_secs(config.reconnect_max_delay),
+        ));
+
+        let timeout = Duration::from_secs(config.connect_timeout);
+        match tokio::time::timeout(timeout, connected.wait_for(|connected| *connected)).await {
+            Ok(Ok(_)) => tracing::info!("MQTT service initialized ({})", config.broker),
+            _ => tracing::warn!(
+                "⚠️  MQTT broker {} not reachable after {}s, continuing degraded (retrying in background)",
+                config.broker,
+                config.connect_timeout
+            ),
+        }
+
+        Ok(Self {
+            client,
+            config,
+            connected,
+            stopping,
+            event_loop: Arc::new(Mutex::new(Some(event_loop))),
+            router,
+            session,
+            default_qos,
+        })
+    }
+
+    /// Drive the connection, tracking its state and dispatching messages until `disconnect` is called
+    ///
+    /// Failed connections are retried with exponential backoff, capped at `max_delay`.
+    async fn run_event_loop(
+        mut event_loop: EventLoop,
+        client: AsyncClient,
+        router: Arc<RwLock<MqttRouter>>,
+        session: Arc<Session>,
+        connected: watch::Sender<bool>,
+        stopping: Arc<AtomicBool>,
+        max_delay: Duration,
+    ) {
+        let mut delay = RECONNECT_MIN_DELAY;
+
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(connack))) => {
+                    tracing::info!("MQTT connected successfully");
+                    connected.send_replace(true);
+                    delay = RECONNECT_MIN_DELAY;
+
+                    // Awaiting client requests here would deadlock, since only this loop drains them
+                    tokio::spawn(Self::restore_session(client.clone(), session.clone(), !connack.session_present));
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let Ok(topic) = String::from_utf8(publish.topic.to_vec()) else {
+                        tracing::warn!("Ignoring MQTT message with a non UTF-8 topic");
+                        Self::ack(&client, publish, Vec::new());
+                        continue;
+                    };
+                    tracing::debug!("Received MQTT message on topic: {}", topic);
+
+                    if topic == session.response_topic {
+                        Self::ack(&client, publish.clone(), Vec::new());
+                        Self::complete_request(&session, publish).await;
+                        continue;
                     }
-                    Err(e) => {
-                        tracing::error!("MQTT connection error: {}", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
+
+                    let message = MqttMessage::from_publish(topic, publish.clone());
+                    let handlers = router.read().await.dispatch(&message);
+                    if handlers.is_empty() {
+                        tracing::debug!("No MQTT route for topic: {}", message.topic);
                     }
+                    Self::ack(&client, publish, handlers);
                 }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => {
+                    connected.send_replace(false);
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    connected.send_replace(false);
+                    if stopping.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    tracing::error!("MQTT connection error: {} (retrying in {}s)", e, delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(max_delay);
+                }
+            }
+        }
+
+        tracing::info!("MQTT event loop stopped");
+    }
+
+    /// Acknowledge a QoS 1/2 message once its handlers are done, unless one of them vetoes it
+    ///
+    /// Runs on its own task: awaiting client requests in the event loop would deadlock.
+    fn ack(client: &AsyncClient, publish: Publish, handlers: Vec<JoinHandle<bool>>) {
+        let client = client.clone();
+
+        tokio::spawn(async move {
+            let mut ack = true;
+            for handler in handlers {
+                // A panicked handler counts as a veto
+                ack &= handler.await.unwrap_or(false);
+            }
+
+            if !ack {
+                tracing::warn!(
+                    "MQTT message on '{}' left unacknowledged, redelivered after reconnecting if the broker kept the session",
+                    String::from_utf8_lossy(&publish.topic)
+                );
+                return;
+            }
+            if let Err(e) = client.ack(&publish).await {
+                tracing::warn!("Failed to acknowledge MQTT message: {}", e);
             }
         });
+    }
 
-        tracing::info!("MQTT service initialized");
+    /// Announce presence, resubscribe (when the broker kept no session) and flush publishes buffered while offline
+    async fn restore_session(client: AsyncClient, session: Arc<Session>, resubscribe: bool) {
+        if let Some((topic, payload)) = &session.presence {
+            if let Err(e) = client.publish(topic, QoS::AtLeastOnce, true, payload.clone()).await {
+                tracing::error!("Failed to publish MQTT presence to '{}': {}", topic, e);
+            }
+        }
 
-        Ok(Self { client, config })
+        if resubscribe {
+            let filters: Vec<Filter> = session
+                .subscriptions
+                .read()
+                .await
+                .iter()
+                .map(|(topic, qos)| Filter::new(topic.clone(), *qos))
+                .collect();
+
+            if !filters.is_empty() {
+                let count = filters.len();
+                match client.subscribe_many(filters).await {
+                    Ok(()) => tracing::info!("Restored {} MQTT subscription(s)", count),
+                    Err(e) => tracing::error!("Failed to restore MQTT subscriptions: {}", e),
+                }
+            }
+        }
+
+        let mut offline = session.offline.lock().await;
+        let mut flushed = 0;
+        while let Some(message) = offline.pop_front() {
+            if let Err(e) = client
+                .publish_bytes(message.topic.clone(), message.qos, message.retain, message.payload.clone())
+                .await
+            {
+                tracing::error!("Failed to flush buffered MQTT publish to '{}': {}", message.topic, e);
+                offline.push_front(message);
+                break;
+            }
+            flushed += 1;
+        }
+
+        if flushed > 0 {
+            tracing::info!("Flushed {} buffered MQTT publish(es)", flushed);
+        }
     }
 
-    /// Parse broker URL to extract host and port
-    fn parse_broker_url(url: &str) -> Result<(String, u16), AppError> {
-        let url = url.trim_start_matches("mqtt://").trim_start_matches("mqtts://");
+    /// Hand a reply to the request waiting for its correlation data
+    async fn complete_request(session: &Session, publish: Publish) {
+        let Some(correlation_data) = publish
+            .properties
+            .as_ref()
+            .and_then(|properties| properties.correlation_data.clone())
+        else {
+            tracing::warn!("Ignoring MQTT reply without correlation data");
+            return;
+        };
+
+        match session.pending.lock().await.remove(&correlation_data) {
+            Some(waiter) => {
+                // The requester may have timed out in the meantime
+                let _ = waiter.send(publish);
+            }
+            None => tracing::debug!("Ignoring late or unknown MQTT reply"),
+        }
+    }
+
+    /// Whether the broker connection is currently up
+    pub fn is_connected(&self) -> bool {
+        *self.connected.borrow()
+    }
 
-        if let Some((host, port_str)) = url.split_once(':') {
-            let port = port_str.parse::<u16>()
-                .map_err(|_| AppError::MqttError(format!("Invalid port in broker URL: {}", port_str)))?;
-            Ok((host.to_string(), port))
+    /// Fail fast instead of queueing requests while the broker is unreachable
+    fn ensure_connected(&self) -> Result<(), AppError> {
+        if self.is_connected() {
+            Ok(())
         } else {
-            Ok((url.to_string(), 1883)) // Default MQTT port
+            Err(AppError::MqttError(format!("MQTT broker {} is unavailable", self.config.broker)))
         }
     }
 
-    /// Subscribe to a topic
+    /// Connection options for the broker URL: `mqtt://`, `mqtts://`, `ws://` or `wss://`
+    async fn broker_options(config: &MqttConfig) -> Result<MqttOptions, AppError> {
+        let (scheme, address) = config.broker.split_once("://").unwrap_or(("mqtt", &config.broker));
+        let scheme = scheme.to_lowercase();
+
+        let transport = match scheme.as_str() {
+            "mqtt" | "tcp" => Transport::Tcp,
+            "mqtts" | "ssl" => Transport::Tls(Self::tls_configuration(config).await?),
+            "ws" => Transport::Ws,
+            "wss" => Transport::Wss(Self::tls_configuration(config).await?),
+            other => {
+                return Err(AppError::MqttError(format!(
+                    "Unsupported MQTT broker scheme '{}' (expected mqtt, mqtts, ws or wss)",
+                    other
+                )))
+            }
+        };
+
+        let uses_tls = matches!(transport, Transport::Tls(_) | Transport::Wss(_));
+        let has_tls_settings = config.ca_file.is_some()
+            || config.client_cert_file.is_some()
+            || config.client_key_file.is_some()
+            || !config.alpn.is_empty();
+        if has_tls_settings && !uses_tls {
+            return Err(AppError::MqttError(format!(
+                "MQTT TLS settings are configured but broker '{}' is not mqtts:// or wss://",
+                config.broker
+            )));
+        }
+
+        let mut mqtt_options = match transport {
+            // WebSocket transports connect to the full URL (host, port and path are taken from it)
+            Transport::Ws | Transport::Wss(_) => {
+                let url = format!("{}://{}", scheme, address);
+                MqttOptions::new(&config.client_id, url, 0)
+            }
+            _ => {
+                let default_port = if uses_tls { 8883 } else { 1883 };
+                let (host, port) = Self::parse_host_port(address, default_port)?;
+                MqttOptions::new(&config.client_id, host, port)
+            }
+        };
+        mqtt_options.set_transport(transport);
+
+        Ok(mqtt_options)
+    }
+
+    /// Split `host[:port]` (IPv6 hosts in brackets), falling back to the scheme's default port
+    fn parse_host_port(address: &str, default_port: u16) -> Result<(String, u16), AppError> {
+        let address = address.trim_end_matches('/');
+
+        let (host, port) = match address.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, rest) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| AppError::MqttError(format!("Invalid broker address: {}", address)))?;
+                (host, rest.strip_prefix(':'))
+            }
+            None => match address.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (address, None),
+            },
+        };
+
+        let port = match port {
+            Some(port_str) => port_str
+                .parse::<u16>()
+                .map_err(|_| AppError::MqttError(format!("Invalid port in broker URL: {}", port_str)))?,
+            None => default_port,
+        };
+
+        Ok((host.to_string(), port))
+    }
+
+    /// TLS settings: configured CA bundle (or the system roots), optional client certificate and ALPN
+    async fn tls_configuration(config: &MqttConfig) -> Result<TlsConfiguration, AppError> {
+        let ca = match &config.ca_file {
+            Some(path) => Self::read_pem(path, "CA bundle").await?,
+            None => Self::native_roots_pem()?,
+        };
+
+        let client_auth = match (&config.client_cert_file, &config.client_key_file) {
+            (Some(cert), Some(key)) => Some((
+                Self::read_pem(cert, "client certificate").await?,
+                Self::read_pem(key, "client key").await?,
+            )),
+            (None, None) => None,
+            _ => {
+                return Err(AppError::MqttError(
+                    "MQTT_CLIENT_CERT_FILE and MQTT_CLIENT_KEY_FILE must be set together".to_string(),
+                ))
+            }
+        };
+
+        let alpn = (!config.alpn.is_empty())
+            .then(|| config.alpn.iter().map(|protocol| protocol.as_bytes().to_vec()).collect());
+
+        Ok(TlsConfiguration::Simple { ca, alpn, client_auth })
+    }
+
+    async fn read_pem(path: &str, what: &str) -> Result<Vec<u8>, AppError> {
+        tokio::fs::read(path)
+            .await
+            .map_err(|e| AppError::MqttError(format!("Failed to read MQTT {} {}: {}", what, path, e)))
+    }
+
+    /// System trust store as a PEM bundle
+    fn native_roots_pem() -> Result<Vec<u8>, AppError> {
+        let certs = rustls_native_certs::load_native_certs()
+            .map_err(|e| AppError::MqttError(format!("Failed to load system CA certificates: {}", e)))?;
+
+        let mut pem = String::new();
+        for cert in certs {
+            let encoded = STANDARD.encode(cert.as_ref());
+            pem.push_str("-----BEGIN CERTIFICATE-----\n");
+            for line in encoded.as_bytes().chunks(64) {
+                pem.push_str(&String::from_utf8_lossy(line));
+                pem.push('\n');
+            }
+            pem.push_str("-----END CERTIFICATE-----\n");
+        }
+
+        Ok(pem.into_bytes())
+    }
+
+    /// Subscribe to a topic with the default QoS (`MQTT_QOS`)
+    ///
+    /// The subscription is remembered and restored after reconnects; while the broker is
+    /// unreachable it is only recorded and sent once connected.
     pub async fn subscribe(&self, topic: &str) -> Result<(), AppError> {
+        self.subscribe_with_qos(topic, self.default_qos).await
+    }
+
+    /// Subscribe to a topic with an explicit QoS
+    pub async fn subscribe_with_qos(&self, topic: &str, qos: QoS) -> Result<(), AppError> {
+        self.session.subscriptions.write().await.insert(topic.to_string(), qos);
+
+        if !self.is_connected() {
+            tracing::info!("MQTT subscription '{}' recorded, subscribing once connected", topic);
+            return Ok(());
+        }
+
         self.client
-            .subscribe(topic, QoS::AtLeastOnce)
+            .subscribe(topic, qos)
             .await
             .map_err(|e| AppError::MqttError(format!("Failed to subscribe to topic '{}': {}", topic, e)))?;
 
-        tracing::info!("Subscribed to MQTT topic: {}", topic);
+        tracing::info!("Subscribed to MQTT topic: {} ({:?})", topic, qos);
         Ok(())
     }
 
     /// Unsubscribe from a topic
     pub async fn unsubscribe(&self, topic: &str) -> Result<(), AppError> {
+        self.session.subscriptions.write().await.remove(topic);
+
+        if !self.is_connected() {
+            return Ok(());
+        }
+
         self.client
             .unsubscribe(topic)
             .await
@@ -91,15 +507,9 @@ impl MqttService {
         Ok(())
     }
 
-    /// Publish a message to a topic
+    /// Publish a message to a topic with the default QoS
     pub async fn publish(&self, topic: &str, payload: &str, retain: bool) -> Result<(), AppError> {
-        self.client
-            .publish(topic, QoS::AtLeastOnce, retain, payload)
-            .await
-            .map_err(|e| AppError::MqttError(format!("Failed to publish to topic '{}': {}", topic, e)))?;
-
-        tracing::debug!("Published message to MQTT topic: {}", topic);
-        Ok(())
+        self.publish_with_qos(topic, self.default_qos, retain, payload.to_string()).await
     }
 
     /// Publish JSON message to a topic
@@ -110,83 +520,301 @@ impl MqttService {
         self.publish(topic, &json, retain).await
     }
 
-    /// Publish bytes to a topic
+    /// Publish bytes to a topic with the default QoS
     pub async fn publish_bytes(&self, topic: &str, payload: &[u8], retain: bool) -> Result<(), AppError> {
+        self.publish_with_qos(topic, self.default_qos, retain, payload.to_vec()).await
+    }
+
+    /// Publish with an explicit QoS
+    ///
+    /// QoS 1/2 messages are buffered while offline when `MQTT_OFFLINE_BUFFER_SIZE` allows it.
+    pub async fn publish_with_qos(
+        &self,
+        topic: &str,
+        qos: QoS,
+        retain: bool,
+        payload: impl Into<Bytes>,
+    ) -> Result<(), AppError> {
+        let payload = payload.into();
+        if !self.is_connected() && qos != QoS::AtMostOnce && self.config.offline_buffer_size > 0 {
+            return self.buffer_publish(topic, qos, retain, payload).await;
+        }
+
+        self.ensure_connected()?;
         self.client
-            .publish(topic, QoS::AtLeastOnce, retain, payload)
+            .publish_bytes(topic, qos, retain, payload)
             .await
-            .map_err(|e| AppError::MqttError(format!("Failed to publish bytes to topic '{}': {}", topic, e)))?;
+            .map_err(|e| AppError::MqttError(format!("Failed to publish to topic '{}': {}", topic, e)))?;
+
+        tracing::debug!("Published message to MQTT topic: {}", topic);
+        Ok(())
+    }
+
+    async fn buffer_publish(&self, topic: &str, qos: QoS, retain: bool, payload: Bytes) -> Result<(), AppError> {
+        let mut offline = self.session.offline.lock().await;
+        if offline.len() >= self.config.offline_buffer_size {
+            return Err(AppError::MqttError(format!(
+                "MQTT broker {} is unavailable and the offline buffer is full ({} messages)",
+                self.config.broker,
+                offline.len()
+            )));
+        }
+
+        offline.push_back(BufferedPublish {
+            topic: topic.to_string(),
+            qos,
+            retain,
+            payload,
+        });
+        drop(offline);
+
+        // The connection may have come back (and flushed) since the check in `publish_with_qos`
+        if self.is_connected() {
+            tokio::spawn(Self::restore_session(self.client.clone(), self.session.clone(), false));
+        } else {
+            tracing::debug!("Buffered MQTT publish to '{}' until the broker is back", topic);
+        }
 
-        tracing::debug!("Published bytes to MQTT topic: {}", topic);
         Ok(())
     }
 
-    /// Disconnect from MQTT broker
+    /// Publishes waiting for the broker to come back
+    pub async fn buffered_count(&self) -> usize {
+        self.session.offline.lock().await.len()
+    }
+
+    /// Disconnect from MQTT broker and wait for the event loop to stop
     pub async fn disconnect(&self) -> Result<(), AppError> {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Some(event_loop) = self.event_loop.lock().await.take() else {
+            return Ok(());
+        };
+
+        if !self.is_connected() {
+            // Nothing to flush; don't wait out a reconnect delay
+            event_loop.abort();
+            tracing::info!("MQTT broker was not connected, nothing to disconnect");
+            return Ok(());
+        }
+
+        // A clean disconnect suppresses the last will, so announce going offline ourselves
+        if let Some(status_topic) = &self.config.status_topic {
+            if let Err(e) = self
+                .client
+                .publish(status_topic, QoS::AtLeastOnce, true, self.config.offline_payload.clone())
+                .await
+            {
+                tracing::warn!("Failed to publish MQTT offline status: {}", e);
+            }
+        }
+
         self.client
             .disconnect()
             .await
             .map_err(|e| AppError::MqttError(format!("Failed to disconnect: {}", e)))?;
 
+        // The event loop sends the queued offline status and DISCONNECT before it stops
+        let abort = event_loop.abort_handle();
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, event_loop).await.is_err() {
+            abort.abort();
+            return Err(AppError::MqttError(format!(
+                "Event loop did not stop within {}s",
+                DISCONNECT_TIMEOUT.as_secs()
+            )));
+        }
+
         tracing::info!("Disconnected from MQTT broker");
         Ok(())
     }
 
-    /// Create a message handler (subscribe and listen for messages)
-    pub async fn listen<F>(&self, topic: &str, handler: F) -> Result<(), AppError>
+    /// Publish a request and wait for its reply (MQTT 5 request/response)
+    ///
+    /// The request carries this instance's response topic and a unique correlation id, which the
+    /// responder echoes in its reply. Requests are never buffered while offline.
+    pub async fn request(&self, topic: &str, payload: impl Into<Bytes>, timeout: Duration) -> Result<Bytes, AppError> {
+        self.ensure_connected()?;
+
+        let correlation_data = Bytes::copy_from_slice(Uuid::new_v4().as_bytes());
+        let (waiter, reply) = oneshot::channel();
+        self.session.pending.lock().await.insert(correlation_data.clone(), waiter);
+
+        let properties = PublishProperties {
+            response_topic: Some(self.session.response_topic.clone()),
+            correlation_data: Some(correlation_data.clone()),
+            ..PublishProperties::default()
+        };
+        let sent = self
+            .client
+            .publish_with_properties(topic, self.default_qos, false, payload.into(), properties)
+            .await;
+
+        let reply = match sent {
+            Ok(()) => tokio::time::timeout(timeout, reply).await,
+            Err(e) => {
+                self.session.pending.lock().await.remove(&correlation_data);
+                return Err(AppError::MqttError(format!("Failed to publish request to '{}': {}", topic, e)));
+            }
+        };
+
+        let Ok(Ok(reply)) = reply else {
+            self.session.pending.lock().await.remove(&correlation_data);
+            return Err(AppError::MqttError(format!(
+                "No reply to MQTT request on '{}' within {}ms",
+                topic,
+                timeout.as_millis()
+            )));
+        };
+
+        let error = reply.properties.as_ref().and_then(|properties| {
+            properties
+                .user_properties
+                .iter()
+                .find(|(key, _)| key == RPC_ERROR_PROPERTY)
+                .map(|(_, value)| value.clone())
+        });
+        if let Some(error) = error {
+            return Err(AppError::MqttError(format!("MQTT request on '{}' failed: {}", topic, error)));
+        }
+
+        Ok(reply.payload)
+    }
+
+    /// Send a JSON request and deserialize the JSON reply
+    pub async fn request_json<Req, Resp>(&self, topic: &str, payload: &Req, timeout: Duration) -> Result<Resp, AppError>
     where
-        F: Fn(String, Vec<u8>) + Send + Sync + 'static,
+        Req: Serialize,
+        Resp: DeserializeOwned,
     {
-        // Subscribe to topic
-        self.subscribe(topic).await?;
+        let json = serde_json::to_vec(payload)
+            .map_err(|e| AppError::MqttError(format!("Failed to serialize JSON: {}", e)))?;
+        let reply = self.request(topic, json, timeout).await?;
 
-        // Create new event loop for listening
-        let broker_url = self.config.broker.clone();
-        let (host, port) = Self::parse_broker_url(&broker_url)?;
+        serde_json::from_slice(&reply)
+            .map_err(|e| AppError::MqttError(format!("Invalid reply to MQTT request on '{}': {}", topic, e)))
+    }
 
-        let mut mqtt_options = MqttOptions::new(
-            &format!("{}_listener", self.config.client_id),
-            host,
-            port,
-        );
-        mqtt_options.set_keep_alive(Duration::from_secs(self.config.keep_alive));
+    /// Serve requests arriving on a topic pattern, publishing each reply to the requester's response topic
+    ///
+    /// Handler errors are sent back in an `error` user property so requesters fail fast instead
+    /// of timing out. Messages without a response topic are rejected.
+    pub async fn respond<F, Fut>(&self, pattern: &str, handler: F) -> Result<(), AppError>
+    where
+        F: Fn(MqttMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Bytes, AppError>> + Send + 'static,
+    {
+        let client = self.client.clone();
+        let handler = Arc::new(handler);
+
+        self.route_raw(pattern, move |message: MqttMessage| {
+            let client = client.clone();
+            let handler = handler.clone();
+            async move {
+                let Some(response_topic) = message.response_topic.clone() else {
+                    return Err(AppError::MqttError(format!(
+                        "Request on '{}' has no response topic",
+                        message.topic
+                    )));
+                };
+                let topic = message.topic.clone();
+                let correlation_data = message.correlation_data.clone();
+
+                let (payload, user_properties) = match handler(message).await {
+                    Ok(payload) => (payload, Vec::new()),
+                    Err(e) => {
+                        tracing::error!("MQTT responder for '{}' failed: {}", topic, e);
+                        (Bytes::new(), vec![(RPC_ERROR_PROPERTY.to_string(), e.to_string())])
+                    }
+                };
+
+                let properties = PublishProperties {
+                    correlation_data,
+                    user_properties,
+                    ..PublishProperties::default()
+                };
+                client
+                    .publish_with_properties(response_topic, QoS::AtLeastOnce, false, payload, properties)
+                    .await
+                    .map_err(|e| AppError::MqttError(format!("Failed to reply to request on '{}': {}", topic, e)))
+            }
+        })
+        .await
+    }
 
-        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
-            mqtt_options.set_credentials(username, password);
-        }
+    /// Serve JSON requests, deserializing them into `Req` and serializing the handler's `Resp`
+    pub async fn respond_json<Req, Resp, F, Fut>(&self, pattern: &str, handler: F) -> Result<(), AppError>
+    where
+        Req: DeserializeOwned + Send + 'static,
+        Resp: Serialize + Send + 'static,
+        F: Fn(Req, TopicParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, AppError>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+
+        self.respond(pattern, move |message: MqttMessage| {
+            let handler = handler.clone();
+            async move {
+                let request = serde_json::from_slice::<Req>(&message.payload).map_err(|e| {
+                    AppError::BadRequest(format!("Invalid request payload on '{}': {}", message.topic, e))
+                })?;
+                let response = handler(request, message.params).await?;
+
+                serde_json::to_vec(&response)
+                    .map(Bytes::from)
+                    .map_err(|e| AppError::MqttError(format!("Failed to serialize JSON: {}", e)))
+            }
+        })
+        .await
+    }
 
-        let (client, mut event_loop) = AsyncClient::new(mqtt_options, 10);
+    /// Register an async handler for a topic pattern, deserializing JSON payloads into `T`
+    ///
+    /// Patterns support `+`, `#` and named levels (`devices/{id}/telemetry`), captured in
+    /// [`TopicParams`]. Routes are subscribed like [`subscribe`](Self::subscribe) and restored on every reconnect.
+    pub async fn route<T, F, Fut>(&self, pattern: &str, handler: F) -> Result<(), AppError>
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T, TopicParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        self.route_with(pattern, RouteOptions::default(), handler).await
+    }
 
-        // Subscribe with the new client
-        client
-            .subscribe(topic, QoS::AtLeastOnce)
-            .await
-            .map_err(|e| AppError::MqttError(format!("Failed to subscribe listener: {}", e)))?;
+    /// Register a JSON handler with explicit options (e.g. a shared subscription)
+    pub async fn route_with<T, F, Fut>(&self, pattern: &str, options: RouteOptions, handler: F) -> Result<(), AppError>
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T, TopicParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let filter = self.router.write().await.route(pattern, options, handler)?;
+        self.subscribe(&self.route_subscription(&filter, options)).await
+    }
 
-        // Spawn listener task
-        let topic_name = topic.to_string();
-        tokio::spawn(async move {
-            tracing::info!("MQTT listener started for topic: {}", topic_name);
-
-            loop {
-                match event_loop.poll().await {
-                    Ok(notification) => {
-                        if let Event::Incoming(Packet::Publish(publish)) = notification {
-                            let topic = publish.topic.clone();
-                            let payload = publish.payload.to_vec();
-
-                            tracing::debug!("Received MQTT message on topic: {}", topic);
-                            handler(topic, payload);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("MQTT listener error: {}", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
-                    }
-                }
-            }
-        });
+    /// Register an async handler receiving the raw message
+    pub async fn route_raw<F, Fut>(&self, pattern: &str, handler: F) -> Result<(), AppError>
+    where
+        F: Fn(MqttMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        self.route_raw_with(pattern, RouteOptions::default(), handler).await
+    }
 
-        Ok(())
+    /// Register a raw handler with explicit options (e.g. a shared subscription)
+    pub async fn route_raw_with<F, Fut>(&self, pattern: &str, options: RouteOptions, handler: F) -> Result<(), AppError>
+    where
+        F: Fn(MqttMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let filter = self.router.write().await.route_raw(pattern, options, handler)?;
+        self.subscribe(&self.route_subscription(&filter, options)).await
+    }
+
+    /// Subscription filter for a route (`$share/<group>/<filter>` when shared)
+    fn route_subscription(&self, filter: &TopicFilter, options: RouteOptions) -> String {
+        if options.shared {
+            filter.shared_subscription(&self.config.shared_group)
+        } else {
+            filter.subscription()
+        }
     }
 }
@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+use crate::interceptors::AppError;
+use crate::queue::{QueueEvent, QueueEventKind, QueueEventType, QueueManager};
+use crate::services::mqtt_router::MqttMessage;
+use crate::services::mqtt_service::MqttService;
+use crate::services::redis_service::RedisService;
+
+/// MQTT topics forwarded to the user they belong to
+pub const USER_TOPIC: &str = "users/{id}/#";
+
+/// Events held per connection before a slow client starts missing them
+const CONNECTION_BUFFER: usize = 256;
+/// Wait before reconnecting a dropped Redis subscription
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Event pushed to a user's WebSocket and SSE connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserEvent {
+    /// Event type, e.g. `notification`, `telemetry`, `job.completed`
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl UserEvent {
+    pub fn new(event_type: impl Into<String>, data: serde_json::Value) -> Self {
+        Self {
+            event_type: event_type.into(),
+            data,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Event relayed between instances over Redis pub/sub
+#[derive(Debug, Serialize, Deserialize)]
+struct UserEventEnvelope {
+    instance_id: String,
+    user_id: String,
+    event: UserEvent,
+}
+
+/// Per-user senders for the connections open on this instance
+type Connections = Arc<RwLock<HashMap<String, broadcast::Sender<UserEvent>>>>;
+
+/// Pushes events to connected users on every instance
+///
+/// Events are delivered to this instance's connections directly and published on Redis,
+/// where the other instances pick them up for their own connections.
+#[derive(Debug, Clone)]
+pub struct RealtimeHub {
+    redis: RedisService,
+    channel: String,
+    instance_id: String,
+    connections: Connections,
+}
+
+impl RealtimeHub {
+    pub fn new(redis: RedisService, environment: &str) -> Self {
+        Self {
+            redis,
+            channel: format!("{}_user_events", environment),
+            instance_id: Uuid::new_v4().to_string(),
+            connections: Connections::default(),
+        }
+    }
+
+    /// Relay events published by other instances to this instance's connections
+    pub fn start(&self) {
+        let hub = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let mut pubsub = match hub.redis.pubsub().await {
+                    Ok(pubsub) => pubsub,
+                    Err(e) => {
+                        tracing::warn!("User event listener failed to connect: {}", e);
+                        sleep(RECONNECT_DELAY).await;
+                        continue;
+                    }
+                };
+
+                if let Err(e) = pubsub.subscribe(&hub.channel).await {
+                    tracing::warn!("User event listener failed to subscribe to '{}': {}", hub.channel, e);
+                    sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let payload: String = match msg.get_payload() {
+                        Ok(payload) => payload,
+                        Err(_) => continue,
+                    };
+
+                    match serde_json::from_str::<UserEventEnvelope>(&payload) {
+                        Ok(envelope) if envelope.instance_id != hub.instance_id => {
+                            hub.send_to_local_user(&envelope.user_id, envelope.event);
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::debug!("Ignoring malformed user event: {}", e),
+                    }
+                }
+
+                tracing::warn!("User event listener disconnected, reconnecting in 5 seconds...");
+                sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    /// Forward messages on `users/{id}/#` to that user's connections
+    ///
+    /// Every instance holds its own subscription, so each only delivers to its local connections.
+    pub async fn forward_mqtt(&self, mqtt: &MqttService) -> Result<(), AppError> {
+        let hub = self.clone();
+
+        mqtt.route_raw(USER_TOPIC, move |message: MqttMessage| {
+            let hub = hub.clone();
+            async move {
+                let user_id = message.params.require("id")?.to_string();
+                let payload = serde_json::from_slice(&message.payload)
+                    .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&message.payload).into_owned()));
+
+                hub.send_to_local_user(
+                    &user_id,
+                    UserEvent::new("mqtt", serde_json::json!({ "topic": message.topic, "payload": payload })),
+                );
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Push `job.completed` / `job.failed` to the user a queue job runs for
+    ///
+    /// Only jobs added with `add_to_queue_for_user` carry a user. Queue hooks run on the
+    /// instance that finished the job, so events go out through `send_to_user`.
+    pub fn forward_job_events(&self) {
+        let manager = QueueManager::global();
+
+        for event_type in [QueueEventType::Completed, QueueEventType::Failed] {
+            let hub = self.clone();
+            manager.on(event_type, move |event: QueueEvent| {
+                let hub = hub.clone();
+                async move {
+                    let Some(user_id) = event.user_id.as_deref() else {
+                        return;
+                    };
+
+                    let user_event = match &event.kind {
+                        QueueEventKind::Completed { attempt, duration_ms } => UserEvent::new(
+                            "job.completed",
+                            serde_json::json!({
+                                "queue": event.queue,
+                                "job_id": event.job_id,
+                                "attempt": attempt,
+                                "duration_ms": duration_ms,
+                            }),
+                        ),
+                        QueueEventKind::Failed { attempts, error } => UserEvent::new(
+                            "job.failed",
+                            serde_json::json!({
+                                "queue": event.queue,
+                                "job_id": event.job_id,
+                                "attempts": attempts,
+                                "error": error,
+                            }),
+                        ),
+                        _ => return,
+                    };
+
+                    if let Err(e) = hub.send_to_user(user_id, user_event).await {
+                        tracing::warn!("Failed to push job event for job {} to user {}: {}", event.job_id, user_id, e);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Send an event to every connection of a user, on any instance
+    pub async fn send_to_user(&self, user_id: &str, event: UserEvent) -> Result<(), AppError> {
+        self.send_to_local_user(user_id, event.clone());
+
+        let envelope = UserEventEnvelope {
+            instance_id: self.instance_id.clone(),
+            user_id: user_id.to_string(),
+            event,
+        };
+        self.redis.publish(&self.channel, &serde_json::to_string(&envelope)?).await?;
+
+        Ok(())
+    }
+
+    /// Send an event to a user's connections on this instance only
+    ///
+    /// For sources every instance sees anyway, such as (non-shared) MQTT subscriptions.
+    pub fn send_to_local_user(&self, user_id: &str, event: UserEvent) {
+        if let Some(sender) = self.connections.read().unwrap_or_else(|e| e.into_inner()).get(user_id) {
+            // No receivers left is not an error
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Receive a user's events (one subscription per connection)
+    pub fn subscribe(&self, user_id: &str) -> UserSubscription {
+        let receiver = self
+            .connections
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(user_id.to_string())
+            .or_insert_with(|| broadcast::channel(CONNECTION_BUFFER).0)
+            .subscribe();
+
+        UserSubscription {
+            user_id: user_id.to_string(),
+            receiver,
+            connections: self.connections.clone(),
+        }
+    }
+
+    /// Users with at least one open connection on this instance
+    pub fn connected_users(&self) -> usize {
+        self.connections.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+/// A connection's stream of user events; unregisters the user when their last connection closes
+pub struct UserSubscription {
+    user_id: String,
+    receiver: broadcast::Receiver<UserEvent>,
+    connections: Connections,
+}
+
+impl UserSubscription {
+    /// Next event, skipping any the connection was too slow to receive
+    pub async fn recv(&mut self) -> Option<UserEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("⚠️  Connection of user {} fell behind, skipped {} event(s)", self.user_id, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for UserSubscription {
+    fn drop(&mut self) {
+        let mut connections = self.connections.write().unwrap_or_else(|e| e.into_inner());
+
+        // This subscription's receiver is still alive, so 1 means it was the last one
+        if connections
+            .get(&self.user_id)
+            .is_some_and(|sender| sender.receiver_count() <= 1)
+        {
+            connections.remove(&self.user_id);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// Default share of the TTL added at random to each entry
+const DEFAULT_JITTER: f64 = 0.1;
+
+/// Options for `RedisService::get_or_compute`
+#[derive(Debug, Clone, Copy)]
+pub struct CacheOptions {
+    pub ttl: Duration,
+    /// How long an expired value is still served while it is refreshed in the background
+    pub stale_for: Duration,
+    /// How long a `None` from the loader is cached (zero disables negative caching)
+    pub negative_ttl: Duration,
+    /// Up to this share of the TTL is added at random, so entries written together don't expire together
+    pub jitter: f64,
+}
+
+impl CacheOptions {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            stale_for: Duration::ZERO,
+            negative_ttl: Duration::ZERO,
+            jitter: DEFAULT_JITTER,
+        }
+    }
+
+    pub fn with_stale_for(mut self, stale_for: Duration) -> Self {
+        self.stale_for = stale_for;
+        self
+    }
+
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.max(0.0);
+        self
+    }
+
+    /// How long a freshly loaded value counts as fresh (`None` when it shouldn't be cached)
+    pub(crate) fn fresh_for(&self, found: bool) -> Option<Duration> {
+        if !found {
+            return (!self.negative_ttl.is_zero()).then_some(self.negative_ttl);
+        }
+
+        // uuid v4 is random enough to spread expiries without pulling in a RNG
+        let random = (Uuid::new_v4().as_u128() % 10_000) as f64 / 10_000.0;
+        Some(self.ttl + self.ttl.mul_f64(self.jitter * random))
+    }
+}
+
+impl From<Duration> for CacheOptions {
+    fn from(ttl: Duration) -> Self {
+        Self::new(ttl)
+    }
+}
+
+/// Cached entry; `value` is `None` for a cached miss
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedValue<T> {
+    pub value: Option<T>,
+    /// Unix time in milliseconds after which the entry is stale
+    pub fresh_until: i64,
+}
+
+impl<T> CachedValue<T> {
+    pub fn is_fresh(&self) -> bool {
+        chrono::Utc::now().timestamp_millis() < self.fresh_until
+    }
+}
+
+/// Keys currently being loaded in this process, each with the channel its result is shared on
+pub(crate) type Flights = Arc<Mutex<HashMap<String, watch::Receiver<Option<Loaded>>>>>;
+
+/// Result of a flight, shared with its waiters
+#[derive(Debug, Clone)]
+pub(crate) enum Loaded {
+    /// The loader found a value (as JSON)
+    Found(String),
+    /// The loader returned `None`
+    Missing,
+}
+
+/// A caller's place in the load of a key
+pub(crate) enum Flight {
+    /// This caller loads the key and shares the result
+    Lead(FlightGuard),
+    /// Another caller in this process is already loading it
+    Wait(watch::Receiver<Option<Loaded>>),
+}
+
+/// Exclusive right to load a key in this process; forgets the key when dropped
+pub(crate) struct FlightGuard {
+    key: String,
+    flights: Flights,
+    result: watch::Sender<Option<Loaded>>,
+}
+
+impl FlightGuard {
+    /// Lead the load of `key`, or follow the load already in progress in this process
+    pub fn join(flights: &Flights, key: &str) -> Flight {
+        let mut map = flights.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(result) = map.get(key) {
+            return Flight::Wait(result.clone());
+        }
+
+        let (result, receiver) = watch::channel(None);
+        map.insert(key.to_string(), receiver);
+        Flight::Lead(Self {
+            key: key.to_string(),
+            flights: flights.clone(),
+            result,
+        })
+    }
+
+    /// Take over loading `key` unless it is already being loaded in this process
+    pub fn try_join(flights: &Flights, key: &str) -> Option<Self> {
+        match Self::join(flights, key) {
+            Flight::Lead(guard) => Some(guard),
+            Flight::Wait(_) => None,
+        }
+    }
+
+    /// Hand the loaded value to the callers waiting on this flight
+    pub fn finish<T: Serialize>(self, value: &Option<T>) {
+        let loaded = match value.as_ref().map(serde_json::to_string) {
+            Some(Ok(json)) => Loaded::Found(json),
+            Some(Err(e)) => {
+                tracing::warn!("Failed to share loaded value of '{}': {}", self.key, e);
+                return;
+            }
+            None => Loaded::Missing,
+        };
+        self.result.send_replace(Some(loaded));
+    }
+
+    /// Wait for the leader's value; `None` if the load failed and the caller should take over
+    pub async fn wait<T: DeserializeOwned>(mut result: watch::Receiver<Option<Loaded>>) -> Option<Option<T>> {
+        let loaded = result.wait_for(Option::is_some).await.ok()?.clone()?;
+        match loaded {
+            Loaded::Found(json) => serde_json::from_str(&json).ok().map(Some),
+            Loaded::Missing => Some(None),
+        }
+    }
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        // Later callers start a new flight (or find the entry that was just written)
+        self.flights.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lead(flights: &Flights, key: &str) -> FlightGuard {
+        match FlightGuard::join(flights, key) {
+            Flight::Lead(guard) => guard,
+            Flight::Wait(_) => panic!("'{}' should have no flight yet", key),
+        }
+    }
+
+    fn follow(flights: &Flights, key: &str) -> watch::Receiver<Option<Loaded>> {
+        match FlightGuard::join(flights, key) {
+            Flight::Wait(result) => result,
+            Flight::Lead(_) => panic!("'{}' should already be in flight", key),
+        }
+    }
+
+    #[test]
+    fn jitter_stays_within_its_share_of_the_ttl() {
+        let ttl = Duration::from_secs(100);
+        let options = CacheOptions::new(ttl).with_jitter(0.2);
+
+        for _ in 0..1_000 {
+            let fresh_for = options.fresh_for(true).unwrap();
+            assert!(fresh_for >= ttl && fresh_for <= ttl + Duration::from_secs(20), "{:?}", fresh_for);
+        }
+        assert_eq!(CacheOptions::new(ttl).with_jitter(0.0).fresh_for(true), Some(ttl));
+        assert_eq!(CacheOptions::new(ttl).with_jitter(-1.0).jitter, 0.0);
+    }
+
+    #[test]
+    fn misses_are_cached_only_with_a_negative_ttl() {
+        let options = CacheOptions::new(Duration::from_secs(60));
+        assert_eq!(options.fresh_for(false), None);
+
+        let options = options.with_negative_ttl(Duration::from_secs(5));
+        assert_eq!(options.fresh_for(false), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn entries_go_stale_at_fresh_until() {
+        let now = chrono::Utc::now().timestamp_millis();
+        let fresh = CachedValue { value: Some(1), fresh_until: now + 60_000 };
+        let stale = CachedValue { value: Some(1), fresh_until: now - 1 };
+
+        assert!(fresh.is_fresh());
+        assert!(!stale.is_fresh());
+
+        // A cached miss round-trips as a miss, not as an unreadable entry
+        let json = serde_json::to_string(&CachedValue::<u32> { value: None, fresh_until: now }).unwrap();
+        let miss: CachedValue<u32> = serde_json::from_str(&json).unwrap();
+        assert!(miss.value.is_none());
+    }
+
+    #[tokio::test]
+    async fn waiters_share_the_leaders_value() {
+        let flights = Flights::default();
+        let leader = lead(&flights, "k");
+        let (first, second) = (follow(&flights, "k"), follow(&flights, "k"));
+        assert!(FlightGuard::try_join(&flights, "k").is_none());
+
+        leader.finish(&Some(42u32));
+
+        assert_eq!(FlightGuard::wait::<u32>(first).await, Some(Some(42)));
+        assert_eq!(FlightGuard::wait::<u32>(second).await, Some(Some(42)));
+        // The key is forgotten, so the next miss starts a new flight
+        assert!(FlightGuard::try_join(&flights, "k").is_some());
+    }
+
+    #[tokio::test]
+    async fn waiters_share_a_missing_value() {
+        let flights = Flights::default();
+        let leader = lead(&flights, "k");
+        let waiter = follow(&flights, "k");
+
+        leader.finish(&None::<u32>);
+
+        assert_eq!(FlightGuard::wait::<u32>(waiter).await, Some(None));
+    }
+
+    #[tokio::test]
+    async fn values_serialized_as_null_are_still_found() {
+        let flights = Flights::default();
+        let leader = lead(&flights, "k");
+        let waiter = follow(&flights, "k");
+
+        leader.finish(&Some(serde_json::Value::Null));
+
+        assert_eq!(FlightGuard::wait::<serde_json::Value>(waiter).await, Some(Some(serde_json::Value::Null)));
+    }
+
+    #[tokio::test]
+    async fn waiters_take_over_when_the_load_fails() {
+        let flights = Flights::default();
+        let leader = lead(&flights, "k");
+        let waiter = follow(&flights, "k");
+
+        drop(leader);
+
+        assert_eq!(FlightGuard::wait::<u32>(waiter).await, None);
+        lead(&flights, "k");
+    }
+}
@@ -0,0 +1,250 @@
+use once_cell::sync::Lazy;
+use redis::Script;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Instant};
+
+use crate::interceptors::AppError;
+use crate::services::redis_service::RedisService;
+
+/// Extend the lease only if we still hold the lock
+static RENEW_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0"#,
+    )
+});
+
+/// Delete the key only if we still hold the lock
+static RELEASE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0"#,
+    )
+});
+
+/// Held distributed lock
+///
+/// The lease is renewed every third of its TTL while the guard is alive. Dropping the guard
+/// releases the lock in the background; call `release` to release it immediately.
+pub struct RedisLock {
+    redis: RedisService,
+    name: String,
+    key: String,
+    token: String,
+    lost: watch::Receiver<bool>,
+    renewal: JoinHandle<()>,
+    released: bool,
+}
+
+impl RedisLock {
+    pub(crate) fn new(
+        redis: RedisService,
+        name: &str,
+        key: String,
+        token: String,
+        ttl: Duration,
+        acquired_at: Instant,
+    ) -> Self {
+        let (lost_tx, lost) = watch::channel(false);
+        let renewal = tokio::spawn(Self::renew(
+            redis.clone(),
+            name.to_string(),
+            key.clone(),
+            token.clone(),
+            ttl,
+            acquired_at,
+            lost_tx,
+        ));
+
+        Self {
+            redis,
+            name: name.to_string(),
+            key,
+            token,
+            lost,
+            renewal,
+            released: false,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// False once the lease could not be renewed in time (about to expire, expired or taken over)
+    pub fn is_held(&self) -> bool {
+        !*self.lost.borrow()
+    }
+
+    /// Resolves when the lease is lost
+    pub async fn lost(&mut self) {
+        let _ = self.lost.wait_for(|lost| *lost).await;
+    }
+
+    /// Release the lock now
+    pub async fn release(mut self) -> Result<(), AppError> {
+        self.released = true;
+        self.renewal.abort();
+        Self::delete(&self.redis, &self.key, &self.token).await
+    }
+
+    /// Extend the lease every third of its TTL until the guard goes away
+    ///
+    /// The lease counts as lost as soon as the next renewal could not land before it expires,
+    /// so holders stop before another instance can take the lock. Each attempt is bounded by
+    /// the renewal interval, so an unresponsive Redis can't hold this up.
+    async fn renew(
+        redis: RedisService,
+        name: String,
+        key: String,
+        token: String,
+        ttl: Duration,
+        acquired_at: Instant,
+        lost: watch::Sender<bool>,
+    ) {
+        let interval = ttl / 3;
+        // The lease runs from when the last successful request was sent
+        let mut renewed_at = acquired_at;
+
+        loop {
+            sleep(interval).await;
+
+            let sent_at = Instant::now();
+            let result: Result<i64, AppError> = timeout(interval, async {
+                let mut conn = redis.get_connection().await?;
+                Ok(RENEW_SCRIPT
+                    .key(&key)
+                    .arg(&token)
+                    .arg(ttl.as_millis() as u64)
+                    .invoke_async(&mut conn)
+                    .await?)
+            })
+            .await
+            .unwrap_or_else(|_| Err(AppError::RedisError("Timeout renewing lock".to_string())));
+
+            match result {
+                Ok(1) => renewed_at = sent_at,
+                Ok(_) => {
+                    tracing::warn!("🔒 Lock '{}' expired or was taken over", name);
+                    break;
+                }
+                Err(e) if renewed_at.elapsed() + interval >= ttl => {
+                    tracing::warn!("🔒 Lock '{}' lost, lease could not be renewed before it expires: {}", name, e);
+                    break;
+                }
+                Err(e) => tracing::warn!("Failed to renew lock '{}', retrying: {}", name, e),
+            }
+        }
+
+        let _ = lost.send(true);
+    }
+
+    async fn delete(redis: &RedisService, key: &str, token: &str) -> Result<(), AppError> {
+        let mut conn = redis.get_connection().await?;
+        let _: i64 = RELEASE_SCRIPT.key(key).arg(token).invoke_async(&mut conn).await?;
+        Ok(())
+    }
+}
+
+impl Drop for RedisLock {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        self.renewal.abort();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let (redis, key, token, name) = (self.redis.clone(), self.key.clone(), self.token.clone(), self.name.clone());
+            runtime.spawn(async move {
+                if let Err(e) = Self::delete(&redis, &key, &token).await {
+                    tracing::warn!("Failed to release lock '{}' (it expires with its lease): {}", name, e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! These need a Redis server (`REDIS_HOST` / `REDIS_PORT`): `cargo test -- --ignored`
+
+    use super::*;
+    use uuid::Uuid;
+
+    async fn redis() -> RedisService {
+        RedisService::new("test").await.expect("lock tests need a Redis server")
+    }
+
+    fn unique(name: &str) -> String {
+        format!("{}-{}", name, Uuid::new_v4().simple())
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn lock_is_exclusive_until_released() {
+        let redis = redis().await;
+        let name = unique("exclusive");
+
+        let lock = redis.lock(&name, Duration::from_secs(5)).await.unwrap().expect("free lock");
+        assert!(redis.lock(&name, Duration::from_secs(5)).await.unwrap().is_none());
+
+        lock.release().await.unwrap();
+        assert!(redis.lock(&name, Duration::from_secs(5)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn lease_is_renewed_past_its_ttl() {
+        let redis = redis().await;
+        let name = unique("renewed");
+
+        let lock = redis.lock(&name, Duration::from_millis(300)).await.unwrap().expect("free lock");
+        sleep(Duration::from_millis(900)).await;
+
+        assert!(lock.is_held());
+        assert!(redis.lock(&name, Duration::from_millis(300)).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn release_leaves_a_lock_taken_over_by_someone_else() {
+        let redis = redis().await;
+        let name = unique("taken-over");
+
+        let mut lock = redis.lock(&name, Duration::from_millis(300)).await.unwrap().expect("free lock");
+        let key = lock.key.clone();
+        redis.set(&key, "someone-else").await.unwrap();
+
+        timeout(Duration::from_secs(1), lock.lost()).await.expect("lease loss is reported");
+        assert!(!lock.is_held());
+
+        lock.release().await.unwrap();
+        assert_eq!(redis.get(&key).await.unwrap().as_deref(), Some("someone-else"));
+        redis.del(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn with_lock_fails_fast_while_held() {
+        let redis = redis().await;
+        let name = unique("with-lock");
+
+        let _held = redis.lock(&name, Duration::from_secs(5)).await.unwrap().expect("free lock");
+        let result = redis.with_lock(&name, Duration::from_secs(5), Duration::ZERO, || async { Ok(()) }).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    #[should_panic(expected = "lock TTL must be at least")]
+    async fn ttl_too_short_to_renew_is_rejected() {
+        let _ = redis().await.lock(&unique("short"), Duration::from_millis(2)).await;
+    }
+}
@@ -1,18 +1,42 @@
 use deadpool_redis::{Connection, Pool};
 use deadpool_redis::redis::AsyncCommands;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use uuid::Uuid;
 
 use crate::config::RedisConfig;
 use crate::interceptors::AppError;
+use crate::services::redis_cache::{CacheOptions, CachedValue, Flight, FlightGuard, Flights};
+use crate::services::redis_lock::RedisLock;
+
+/// How often a waiting `lock_wait` retries
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// Shortest lock lease (3 × `LOCK_RETRY_INTERVAL`); shorter ones leave no room to renew
+const MIN_LOCK_TTL: Duration = Duration::from_millis(300);
+/// Lease of the lock one instance holds while loading a cache entry
+const CACHE_LOAD_LOCK_TTL: Duration = Duration::from_secs(5);
+/// How long other instances wait for that load before loading themselves
+const CACHE_LOAD_WAIT: Duration = Duration::from_secs(5);
+/// How often a waiting instance checks whether the entry has been written
+const CACHE_LOAD_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 #[derive(Debug, Clone)]
 pub struct RedisService {
     pool: Pool,
+    /// Used for pub/sub, which needs a dedicated connection outside the pool
+    client: redis::Client,
+    /// Cache keys being loaded by `get_or_compute` in this process
+    flights: Flights,
+    /// Prefix of cache and lock keys, so environments can share one Redis
+    environment: String,
 }
 
 impl RedisService {
     /// Create a new RedisService instance
-    pub async fn new() -> Result<Self, AppError> {
+    pub async fn new(environment: &str) -> Result<Self, AppError> {
         let config = RedisConfig::from_env()
             .map_err(|e| AppError::RedisError(format!("Failed to load Redis config: {}", e)))?;
 
@@ -28,9 +52,17 @@ impl RedisService {
             .await
             .map_err(|e| AppError::RedisError(format!("Redis ping failed: {}", e)))?;
 
+        let client = redis::Client::open(config.build_redis_url())
+            .map_err(|e| AppError::RedisError(format!("Failed to create Redis client: {}", e)))?;
+
         tracing::info!("Redis service initialized successfully");
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            client,
+            flights: Flights::default(),
+            environment: environment.to_string(),
+        })
     }
 
     /// Get a connection from the pool
@@ -103,6 +135,24 @@ impl RedisService {
             .map_err(|e| AppError::RedisError(e.to_string()))
     }
 
+    // Pub/Sub operations
+
+    /// Publish a message on a channel, returning the number of subscribers that received it
+    pub async fn publish(&self, channel: &str, message: &str) -> Result<i64, AppError> {
+        let mut conn = self.get_connection().await?;
+        conn.publish(channel, message)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))
+    }
+
+    /// Open a dedicated pub/sub connection
+    pub async fn pubsub(&self) -> Result<redis::aio::PubSub, AppError> {
+        self.client
+            .get_async_pubsub()
+            .await
+            .map_err(|e| AppError::RedisError(format!("Failed to open pub/sub connection: {}", e)))
+    }
+
     // JSON helpers
 
     /// Set a JSON value
@@ -211,11 +261,95 @@ impl RedisService {
             .map_err(|e| AppError::RedisError(e.to_string()))
     }
 
+    // Distributed locks
+
+    /// Try to take the lock `name` once (`SET NX PX` with a unique token)
+    ///
+    /// Returns `None` if another holder has it. The lease lasts `ttl` and is extended
+    /// automatically while the returned guard is alive.
+    ///
+    /// # Panics
+    ///
+    /// If `ttl` is shorter than `MIN_LOCK_TTL`.
+    pub async fn lock(&self, name: &str, ttl: Duration) -> Result<Option<RedisLock>, AppError> {
+        assert!(ttl >= MIN_LOCK_TTL, "lock TTL must be at least {:?}, got {:?}", MIN_LOCK_TTL, ttl);
+
+        let key = self.lock_key(name);
+        let token = Uuid::new_v4().to_string();
+        // The lease runs from when we asked for it, not from when the reply arrived
+        let requested_at = Instant::now();
+
+        let mut conn = self.get_connection().await?;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::RedisError(e.to_string()))?;
+
+        Ok(acquired.map(|_| RedisLock::new(self.clone(), name, key, token, ttl, requested_at)))
+    }
+
+    /// Take the lock `name`, waiting up to `timeout` for the current holder to release it
+    pub async fn lock_wait(&self, name: &str, ttl: Duration, timeout: Duration) -> Result<Option<RedisLock>, AppError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(lock) = self.lock(name, ttl).await? {
+                return Ok(Some(lock));
+            }
+            if Instant::now() + LOCK_RETRY_INTERVAL > deadline {
+                return Ok(None);
+            }
+            sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Run `task` while holding the lock `name`
+    ///
+    /// Waits up to `wait` for the lock (`Duration::ZERO` fails fast) and fails with `CONFLICT`
+    /// if it stays taken. If the lease is lost while `task` runs, `task` is cancelled rather
+    /// than left running without exclusion.
+    pub async fn with_lock<T, F, Fut>(&self, name: &str, ttl: Duration, wait: Duration, task: F) -> Result<T, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let mut lock = self
+            .lock_wait(name, ttl, wait)
+            .await?
+            .ok_or_else(|| AppError::Conflict(format!("Lock '{}' is held by another process", name)))?;
+
+        let result = tokio::select! {
+            result = task() => result,
+            _ = lock.lost() => Err(AppError::Conflict(format!("Lock '{}' was lost before the task finished", name))),
+        };
+
+        if let Err(e) = lock.release().await {
+            tracing::warn!("Failed to release lock '{}' (it expires with its lease): {}", name, e);
+        }
+
+        result
+    }
+
     // Cache helpers with prefix
 
     /// Generate a cache key with prefix
     pub fn cache_key(&self, prefix: &str, key: &str) -> String {
-        format!("cache:{}:{}", prefix, key)
+        self.prefixed_key(&format!("cache:{}:{}", prefix, key))
+    }
+
+    /// Redis key of the lock `name`
+    fn lock_key(&self, name: &str) -> String {
+        self.prefixed_key(&format!("lock:{}", name))
+    }
+
+    /// Scope a key to this environment (`{ENVIRONMENT}_{key}`)
+    fn prefixed_key(&self, key: &str) -> String {
+        format!("{}_{}", self.environment, key)
     }
 
     /// Set cache with TTL
@@ -247,4 +381,182 @@ impl RedisService {
         let cache_key = self.cache_key(prefix, key);
         self.get_json(&cache_key).await
     }
+
+    /// Get a cached value, or load and cache it on a miss (cache-aside)
+    ///
+    /// Concurrent misses for the same key share one `loader` call, in this process and across
+    /// instances (a short Redis lock; other instances wait for the cached result, or load it
+    /// themselves once the lock is released without one). A `None` from the loader is cached
+    /// for `negative_ttl`. Within `stale_for` after expiry the old value is
+    /// returned while one caller refreshes it in the background. Entries use their own format,
+    /// so don't mix them with `cache_set_json` on the same key. If Redis is unavailable the
+    /// value is loaded without caching.
+    pub async fn get_or_compute<T, F, Fut>(
+        &self,
+        prefix: &str,
+        key: &str,
+        options: impl Into<CacheOptions>,
+        loader: F,
+    ) -> Result<Option<T>, AppError>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Option<T>, AppError>> + Send + 'static,
+    {
+        let options = options.into();
+        let cache_key = self.cache_key(prefix, key);
+
+        match self.read_cached::<T>(&cache_key).await {
+            Some(cached) if cached.is_fresh() => return Ok(cached.value),
+            Some(cached) => {
+                self.spawn_cache_refresh(cache_key, Self::cache_load_lock(prefix, key), options, loader);
+                return Ok(cached.value);
+            }
+            None => {}
+        }
+
+        let flight = loop {
+            match FlightGuard::join(&self.flights, &cache_key) {
+                Flight::Lead(flight) => break flight,
+                Flight::Wait(result) => {
+                    if let Some(value) = FlightGuard::wait::<T>(result).await {
+                        return Ok(value);
+                    }
+                    // That load failed; try to take over
+                }
+            }
+        };
+
+        // Written by a caller that finished just before we joined
+        if let Some(cached) = self.read_cached::<T>(&cache_key).await {
+            return Ok(cached.value);
+        }
+
+        let load_lock = Self::cache_load_lock(prefix, key);
+        let value = match self.lock(&load_lock, CACHE_LOAD_LOCK_TTL).await {
+            Ok(Some(lock)) => {
+                let value = loader().await?;
+                self.write_cached(&cache_key, &options, &value).await;
+                if let Err(e) = lock.release().await {
+                    tracing::warn!("Failed to release lock '{}': {}", load_lock, e);
+                }
+                value
+            }
+            Ok(None) => match self.wait_for_load::<T>(&cache_key, &load_lock).await {
+                // Loaded by another instance
+                Some(cached) => cached.value,
+                // It cached nothing (a `None` without negative caching, or a failed write) or timed out
+                None => {
+                    let value = loader().await?;
+                    self.write_cached(&cache_key, &options, &value).await;
+                    value
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Cache lock for '{}' unavailable, loading without caching: {}", cache_key, e);
+                loader().await?
+            }
+        };
+
+        flight.finish(&value);
+        Ok(value)
+    }
+
+    /// Name of the lock held while loading a cache entry (`lock` scopes it to the environment)
+    fn cache_load_lock(prefix: &str, key: &str) -> String {
+        format!("cache:{}:{}:load", prefix, key)
+    }
+
+    /// Poll for an entry another instance is loading until it appears or the load lock is released
+    ///
+    /// Gives up after `CACHE_LOAD_WAIT` in case the other instance hangs.
+    async fn wait_for_load<T: DeserializeOwned>(&self, cache_key: &str, load_lock: &str) -> Option<CachedValue<T>> {
+        let deadline = Instant::now() + CACHE_LOAD_WAIT;
+        let lock_key = self.lock_key(load_lock);
+
+        while Instant::now() < deadline {
+            sleep(CACHE_LOAD_POLL_INTERVAL).await;
+            if let Some(cached) = self.read_cached::<T>(cache_key).await {
+                return Some(cached);
+            }
+            if let Ok(false) = self.exists(&lock_key).await {
+                // Released: the entry was either written just now or won't be
+                return self.read_cached::<T>(cache_key).await;
+            }
+        }
+
+        tracing::warn!("Timed out waiting for another instance to load '{}', loading it here", cache_key);
+        None
+    }
+
+    /// Refresh a stale entry unless this process or another instance is already doing it
+    fn spawn_cache_refresh<T, F, Fut>(&self, cache_key: String, load_lock: String, options: CacheOptions, loader: F)
+    where
+        T: Serialize + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Option<T>, AppError>> + Send + 'static,
+    {
+        let Some(flight) = FlightGuard::try_join(&self.flights, &cache_key) else {
+            return;
+        };
+        let redis = self.clone();
+
+        tokio::spawn(async move {
+            let Ok(Some(lock)) = redis.lock(&load_lock, CACHE_LOAD_LOCK_TTL).await else {
+                return;
+            };
+
+            match loader().await {
+                Ok(value) => {
+                    redis.write_cached(&cache_key, &options, &value).await;
+                    flight.finish(&value);
+                }
+                Err(e) => tracing::warn!("Failed to refresh cache entry '{}', serving stale value: {}", cache_key, e),
+            }
+            let _ = lock.release().await;
+        });
+    }
+
+    async fn read_cached<T: DeserializeOwned>(&self, cache_key: &str) -> Option<CachedValue<T>> {
+        match self.get(cache_key).await {
+            Ok(Some(json)) => serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Ignoring unreadable cache entry '{}': {}", cache_key, e))
+                .ok(),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to read cache entry '{}': {}", cache_key, e);
+                None
+            }
+        }
+    }
+
+    async fn write_cached<T: Serialize>(&self, cache_key: &str, options: &CacheOptions, value: &Option<T>) {
+        let Some(fresh_for) = options.fresh_for(value.is_some()) else {
+            return;
+        };
+
+        let entry = CachedValue {
+            value: value.as_ref(),
+            fresh_until: chrono::Utc::now().timestamp_millis() + fresh_for.as_millis() as i64,
+        };
+        let expires_in = fresh_for + options.stale_for;
+
+        let result = async {
+            let json = serde_json::to_string(&entry)?;
+            let mut conn = self.get_connection().await?;
+            redis::cmd("SET")
+                .arg(cache_key)
+                .arg(json)
+                .arg("PX")
+                .arg(expires_in.as_millis().max(1) as u64)
+                .query_async::<()>(&mut conn)
+                .await?;
+            Ok::<(), AppError>(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to write cache entry '{}': {}", cache_key, e);
+        }
+    }
 }
@@ -0,0 +1,113 @@
+use tracing::{info, warn};
+
+use crate::config::AppState;
+use crate::dto::{BounceType, EmailWebhookEvent, EmailWebhookEventType, EmailWebhookResponse};
+use crate::interceptors::AppError;
+use crate::models::{EmailSuppression, SuppressionReason};
+use crate::services::email_kind::EmailKind;
+
+/// Suppression list and unsubscribe handling
+#[derive(Clone)]
+pub struct SuppressionService {
+    state: AppState,
+}
+
+impl SuppressionService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Suppression entry for an address, if any
+    pub async fn find(&self, email: &str) -> Result<Option<EmailSuppression>, AppError> {
+        let suppression = sqlx::query_as::<_, EmailSuppression>("SELECT * FROM email_suppressions WHERE email = $1")
+            .bind(Self::normalize(email))
+            .fetch_optional(&self.state.db)
+            .await?;
+
+        Ok(suppression)
+    }
+
+    /// Reason the address must not receive this kind of email, if any
+    pub async fn blocking_reason(&self, email: &str, kind: &EmailKind) -> Result<Option<SuppressionReason>, AppError> {
+        let reason = self.find(email).await?.and_then(|suppression| suppression.reason());
+
+        Ok(reason.filter(|reason| reason.blocks_all() || kind.is_unsubscribable()))
+    }
+
+    /// Add an address to the suppression list
+    ///
+    /// Bounces and complaints replace an unsubscribe entry, never the other way around.
+    /// Returns false when the address was already suppressed and nothing changed.
+    pub async fn suppress(&self, email: &str, reason: SuppressionReason, details: Option<&str>) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO email_suppressions (email, reason, details, created_at)
+             VALUES ($1, $2, $3, NOW())
+             ON CONFLICT (email) DO UPDATE
+             SET reason = EXCLUDED.reason, details = EXCLUDED.details, created_at = EXCLUDED.created_at
+             WHERE email_suppressions.reason = 'unsubscribe'",
+        )
+        .bind(Self::normalize(email))
+        .bind(reason.as_str())
+        .bind(details)
+        .execute(&self.state.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        info!("🚫 Suppressed {} ({})", email, reason.as_str());
+        Ok(true)
+    }
+
+    /// Apply bounce/complaint notifications from the provider webhook
+    pub async fn handle_webhook_events(&self, events: Vec<EmailWebhookEvent>) -> Result<EmailWebhookResponse, AppError> {
+        let mut response = EmailWebhookResponse { suppressed: 0, ignored: 0 };
+
+        for event in events {
+            let reason = match (event.event_type, event.bounce_type.unwrap_or(BounceType::Hard)) {
+                (EmailWebhookEventType::Complaint, _) => SuppressionReason::Complaint,
+                (EmailWebhookEventType::Bounce, BounceType::Hard) => SuppressionReason::Bounce,
+                (EmailWebhookEventType::Bounce, BounceType::Soft) => {
+                    warn!("⚠️  Soft bounce for {}: {}", event.email, event.description.as_deref().unwrap_or("-"));
+                    response.ignored += 1;
+                    continue;
+                }
+            };
+
+            if self.suppress(&event.email, reason, event.description.as_deref()).await? {
+                response.suppressed += 1;
+            } else {
+                response.ignored += 1;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Unsubscribe the user owning this token from notification emails
+    pub async fn unsubscribe(&self, token: &str) -> Result<String, AppError> {
+        let email: String = sqlx::query_scalar("SELECT email FROM users WHERE unsubscribe_token = $1")
+            .bind(token)
+            .fetch_optional(&self.state.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Unsubscribe link is invalid or expired".to_string()))?;
+
+        self.suppress(&email, SuppressionReason::Unsubscribe, Some("List-Unsubscribe")).await?;
+        Ok(email)
+    }
+
+    /// Unsubscribe token of the user registered with this email (compared case-insensitively)
+    pub async fn unsubscribe_token_for(&self, email: &str) -> Result<Option<String>, AppError> {
+        let token = sqlx::query_scalar("SELECT unsubscribe_token FROM users WHERE LOWER(email) = $1 LIMIT 1")
+            .bind(Self::normalize(email))
+            .fetch_optional(&self.state.db)
+            .await?;
+
+        Ok(token)
+    }
+
+    fn normalize(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::Utc;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::config::AppState;
+use crate::dto::TelemetryMessage;
+use crate::interceptors::AppError;
+use crate::models::{Device, TelemetryReading};
+use crate::services::device_service::DeviceService;
+use crate::services::mqtt_router::{RouteOptions, TopicParams};
+use crate::services::realtime_service::UserEvent;
+
+/// Topic devices publish telemetry on
+pub const TELEMETRY_TOPIC: &str = "devices/{id}/telemetry";
+
+/// Readings written per INSERT
+const BATCH_SIZE: usize = 500;
+/// Longest time a reading waits before its batch is written
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Readings waiting to be written before new ones are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+/// Readings accepted in one message
+const MAX_READINGS_PER_MESSAGE: usize = 1000;
+/// How long a device lookup (including "unknown device") is reused; key rotation and
+/// deactivation take effect for ingestion within this window
+const DEVICE_CACHE_TTL: Duration = Duration::from_secs(60);
+/// Device lookups kept at most (lookups of made-up device IDs are cached too)
+const DEVICE_CACHE_CAPACITY: usize = 10_000;
+
+/// Cached device lookups, so each message doesn't hit the database
+type DeviceCache = Arc<Mutex<HashMap<String, (Option<Device>, Instant)>>>;
+
+/// Telemetry ingestion from MQTT into the `telemetry` table
+#[derive(Clone)]
+pub struct TelemetryService {
+    state: AppState,
+}
+
+impl TelemetryService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Subscribe to device telemetry and start the batch writer
+    ///
+    /// The subscription is shared, so with several instances each message is ingested once.
+    /// Messages must carry the device's key; readings are queued and written in batches of
+    /// up to `BATCH_SIZE`, at least every `FLUSH_INTERVAL`.
+    pub async fn start(&self) -> Result<(), AppError> {
+        let Some(mqtt) = self.state.mqtt.clone() else {
+            tracing::info!("MQTT disabled, telemetry ingestion not started");
+            return Ok(());
+        };
+
+        let (readings_tx, readings_rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(Self::run_writer(self.state.db.clone(), readings_rx));
+
+        let state = self.state.clone();
+        let cache = DeviceCache::default();
+
+        mqtt.route_with(TELEMETRY_TOPIC, RouteOptions::shared(), move |message: TelemetryMessage, params: TopicParams| {
+            let state = state.clone();
+            let cache = cache.clone();
+            let readings_tx = readings_tx.clone();
+            async move {
+                let device_id = params.require("id")?;
+                let device = Self::cached_device(&state, &cache, device_id).await?;
+
+                if !DeviceService::authenticate(device.as_ref(), &message.key) {
+                    return Err(AppError::Unauthorized(format!(
+                        "Rejected telemetry for device '{}': unknown device, inactive or invalid key",
+                        device_id
+                    )));
+                }
+
+                let readings = Self::validate(device_id, message)?;
+                if let Some(device) = &device {
+                    // Only this instance received the message, so relay to the owner's other connections too
+                    let event = UserEvent::new("telemetry", serde_json::json!({ "device_id": device_id, "readings": readings }));
+                    if let Err(e) = state.realtime.send_to_user(&device.owner_id, event).await {
+                        tracing::warn!("Failed to push telemetry of device {}: {}", device_id, e);
+                    }
+                }
+
+                for reading in readings {
+                    if readings_tx.try_send(reading).is_err() {
+                        tracing::warn!("⚠️  Telemetry queue full, dropping reading from device {}", device_id);
+                        break;
+                    }
+                }
+
+                Ok(())
+            }
+        })
+        .await?;
+
+        tracing::info!("📡 Telemetry ingestion listening on {}", TELEMETRY_TOPIC);
+        Ok(())
+    }
+
+    async fn cached_device(state: &AppState, cache: &DeviceCache, device_id: &str) -> Result<Option<Device>, AppError> {
+        if let Some((device, cached_at)) = cache.lock().await.get(device_id) {
+            if cached_at.elapsed() < DEVICE_CACHE_TTL {
+                return Ok(device.clone());
+            }
+        }
+
+        let device = DeviceService::new(state.clone()).find(device_id).await?;
+
+        let mut cache = cache.lock().await;
+        if cache.len() >= DEVICE_CACHE_CAPACITY {
+            cache.retain(|_, (_, cached_at)| cached_at.elapsed() < DEVICE_CACHE_TTL);
+        }
+        if cache.len() >= DEVICE_CACHE_CAPACITY {
+            let oldest = cache
+                .iter()
+                .min_by_key(|(_, (_, cached_at))| *cached_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(device_id.to_string(), (device.clone(), Instant::now()));
+
+        Ok(device)
+    }
+
+    fn validate(device_id: &str, message: TelemetryMessage) -> Result<Vec<TelemetryReading>, AppError> {
+        if message.readings.len() > MAX_READINGS_PER_MESSAGE {
+            return Err(AppError::ValidationError(format!(
+                "Telemetry message from device '{}' has {} readings (max {})",
+                device_id,
+                message.readings.len(),
+                MAX_READINGS_PER_MESSAGE
+            )));
+        }
+
+        let received_at = Utc::now();
+        message
+            .readings
+            .into_iter()
+            .map(|reading| {
+                if reading.metric.is_empty() || reading.metric.len() > 100 {
+                    return Err(AppError::ValidationError(format!(
+                        "Invalid metric name from device '{}'",
+                        device_id
+                    )));
+                }
+                if !reading.value.is_finite() {
+                    return Err(AppError::ValidationError(format!(
+                        "Non-finite value for metric '{}' from device '{}'",
+                        reading.metric, device_id
+                    )));
+                }
+
+                Ok(TelemetryReading {
+                    device_id: device_id.to_string(),
+                    metric: reading.metric,
+                    value: reading.value,
+                    recorded_at: reading.timestamp.unwrap_or(received_at),
+                })
+            })
+            .collect()
+    }
+
+    /// Collect queued readings into batches and write them
+    async fn run_writer(db: PgPool, mut readings: mpsc::Receiver<TelemetryReading>) {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                reading = readings.recv() => match reading {
+                    Some(reading) => {
+                        batch.push(reading);
+                        if batch.len() >= BATCH_SIZE {
+                            Self::flush(&db, &mut batch).await;
+                        }
+                    }
+                    None => {
+                        Self::flush(&db, &mut batch).await;
+                        break;
+                    }
+                },
+                _ = ticker.tick() => Self::flush(&db, &mut batch).await,
+            }
+        }
+    }
+
+    async fn flush(db: &PgPool, batch: &mut Vec<TelemetryReading>) {
+        if batch.is_empty() {
+            return;
+        }
+
+        if let Err(e) = Self::insert_batch(db, batch).await {
+            tracing::error!("Failed to write {} telemetry reading(s): {}", batch.len(), e);
+        }
+        batch.clear();
+    }
+
+    async fn insert_batch(db: &PgPool, batch: &[TelemetryReading]) -> Result<(), AppError> {
+        let mut insert = QueryBuilder::<Postgres>::new("INSERT INTO telemetry (device_id, metric, value, recorded_at) ");
+        insert.push_values(batch, |mut row, reading| {
+            row.push_bind(&reading.device_id)
+                .push_bind(&reading.metric)
+                .push_bind(reading.value)
+                .push_bind(reading.recorded_at);
+        });
+        insert.build().execute(db).await?;
+
+        let device_ids: Vec<&str> = batch
+            .iter()
+            .map(|reading| reading.device_id.as_str())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        sqlx::query("UPDATE devices SET last_seen_at = NOW() WHERE id = ANY($1)")
+            .bind(&device_ids)
+            .execute(db)
+            .await?;
+
+        tracing::debug!("Wrote {} telemetry reading(s)", batch.len());
+        Ok(())
+    }
+}
@@ -1,10 +1,13 @@
 use tracing::warn;
 
 use crate::config::AppState;
-use crate::dto::{CreateUserRequest, LoginRequest, LoginResponse, RegisterResponse, UpdateUserRequest, UserResponse};
+use crate::dto::{
+    CreateUserRequest, LoginRequest, LoginResponse, NotificationPreferences, RegisterResponse, UpdateUserRequest,
+    UserResponse,
+};
 use crate::interceptors::AppError;
 use crate::middleware::{Claims, generate_token};
-use crate::models::User;
+use crate::models::{NotificationDelivery, User};
 use crate::services::EmailService;
 use crate::utils::{hash_password, validate_request, verify_password};
 
@@ -42,18 +45,20 @@ impl UserService {
         let password_hash = hash_password(&request.password)?;
 
         // Create user
-        let user = User::new(request.email.clone(), password_hash, request.name);
+        let locale = request.locale.unwrap_or_else(|| "en".to_string());
+        let user = User::new(request.email.clone(), password_hash, request.name, locale);
 
         // Insert into database
         let inserted_user = sqlx::query_as::<_, User>(
-            "INSERT INTO users (id, email, password_hash, name, is_active, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "INSERT INTO users (id, email, password_hash, name, locale, is_active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
              RETURNING *",
         )
         .bind(&user.id)
         .bind(&user.email)
         .bind(&user.password_hash)
         .bind(&user.name)
+        .bind(&user.locale)
         .bind(user.is_active)
         .bind(user.created_at)
         .bind(user.updated_at)
@@ -159,6 +164,12 @@ impl UserService {
             param_count += 1;
         }
 
+        if let Some(locale) = &request.locale {
+            query.push_str(&format!(", locale = ${}", param_count));
+            params.push(locale.clone());
+            param_count += 1;
+        }
+
         if let Some(is_active) = request.is_active {
             query.push_str(&format!(", is_active = ${}", param_count));
             params.push(is_active.to_string());
@@ -181,6 +192,38 @@ impl UserService {
         Ok(updated_user.to_response())
     }
 
+    /// Get notification preferences
+    pub async fn get_preferences(&self, user_id: &str) -> Result<NotificationPreferences, AppError> {
+        let delivery: String = sqlx::query_scalar("SELECT notification_delivery FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.state.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(NotificationPreferences {
+            notification_delivery: NotificationDelivery::parse(&delivery).unwrap_or(NotificationDelivery::Instant),
+        })
+    }
+
+    /// Update notification preferences
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        preferences: NotificationPreferences,
+    ) -> Result<NotificationPreferences, AppError> {
+        let result = sqlx::query("UPDATE users SET notification_delivery = $1, updated_at = NOW() WHERE id = $2")
+            .bind(preferences.notification_delivery.as_str())
+            .bind(user_id)
+            .execute(&self.state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Ok(preferences)
+    }
+
     /// Delete user
     pub async fn delete_user(&self, user_id: &str) -> Result<(), AppError> {
         let result = sqlx::query("DELETE FROM users WHERE id = $1")
@@ -0,0 +1,8 @@
+/// Escape text for inclusion in server-rendered HTML
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
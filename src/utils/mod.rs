@@ -1,5 +1,9 @@
 pub mod password;
 pub mod validation;
+pub mod html;
+pub mod secret;
 
 pub use password::{hash_password, verify_password};
 pub use validation::validate_request;
+pub use html::escape_html;
+pub use secret::{constant_time_eq, generate_secret, hash_secret, verify_secret};
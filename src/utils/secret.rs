@@ -0,0 +1,27 @@
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Random 256-bit secret as 64 hex characters (device keys, API credentials)
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 hex digest of a generated secret
+///
+/// Secrets are high-entropy, so a fast hash is enough and keeps per-message checks cheap
+/// (use `hash_password` for anything a human chooses).
+pub fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Check a secret against its stored hash
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    constant_time_eq(hash_secret(secret).as_bytes(), hash.as_bytes())
+}
+
+/// Compare without leaking the position of the first difference
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}